@@ -309,7 +309,7 @@ struct Header {
         IMF_EXPORT
         Imf::Attribute& attribute() const;
 
-    } CPPMM_OPAQUEBYTES;
+    } CPPMM_OPAQUEBYTES CPPMM_TRIVIALLY_MOVABLE CPPMM_TRIVIALLY_COPYABLE;
 
     struct ConstIterator {
         using BoundType = Imf::Header::ConstIterator;
@@ -334,7 +334,7 @@ struct Header {
         IMF_EXPORT
         const Imf::Attribute& attribute() const;
 
-    } CPPMM_OPAQUEBYTES;
+    } CPPMM_OPAQUEBYTES CPPMM_TRIVIALLY_MOVABLE CPPMM_TRIVIALLY_COPYABLE;
 
 } CPPMM_OPAQUEBYTES;
 
@@ -774,6 +774,10 @@ const Imf::TypedAttribute<Imath::V3d>* (Header::*findTypedAttribute_V3d_const)(
     const char name[]) const =
     &Header::findTypedAttribute<Imf::TypedAttribute<Imath::V3d>>;
 
+bool operator==(const Imf::Header::ConstIterator& a,
+                const Imf::Header::ConstIterator& b)
+    CPPMM_RENAME(header_const_iter_eq);
+
 } // namespace OPENEXR_IMF_INTERNAL_NAMESPACE
 
 } // namespace cppmm_bind
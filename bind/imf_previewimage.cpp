@@ -20,10 +20,10 @@ struct PreviewImage {
 
     IMF_EXPORT
     PreviewImage(unsigned int width = 0, unsigned int height = 0,
-                 const Imf::PreviewRgba pixels[] = 0);
+                 const Imf::PreviewRgba pixels[] = 0) CPPMM_RENAME(ctor);
 
     IMF_EXPORT
-    PreviewImage(const Imf::PreviewImage& other);
+    PreviewImage(const Imf::PreviewImage& other) CPPMM_RENAME(copy);
     IMF_EXPORT
     ~PreviewImage();
 
@@ -0,0 +1,58 @@
+//! Compare `Rgba::slice_to_f32()` and `Rgba::slice_from_f32()` against
+//! converting each pixel in turn.
+//!
+//! Run with `cargo bench --bench rgba_convert`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use openexr::rgba::rgba::Rgba;
+
+const NUM_PIXELS: usize = 1920 * 1080;
+
+fn pixels() -> Vec<Rgba> {
+    (0..NUM_PIXELS)
+        .map(|i| {
+            let v = (i % 1024) as f32 / 1024.0;
+            Rgba::from_f32(v, v * 0.5, v * 0.25, 1.0)
+        })
+        .collect()
+}
+
+fn to_f32(c: &mut Criterion) {
+    let src = pixels();
+    let mut dst = vec![[0.0f32; 4]; NUM_PIXELS];
+
+    let mut group = c.benchmark_group("rgba_to_f32");
+    group.bench_function("slice_to_f32", |b| {
+        b.iter(|| Rgba::slice_to_f32(black_box(&src), black_box(&mut dst)))
+    });
+    group.bench_function("per_pixel", |b| {
+        b.iter(|| {
+            for (s, d) in black_box(&src).iter().zip(black_box(&mut dst)) {
+                *d = [s.r.to_f32(), s.g.to_f32(), s.b.to_f32(), s.a.to_f32()];
+            }
+        })
+    });
+    group.finish();
+}
+
+fn from_f32(c: &mut Criterion) {
+    let mut src = vec![[0.0f32; 4]; NUM_PIXELS];
+    Rgba::slice_to_f32(&pixels(), &mut src);
+    let mut dst = vec![Rgba::default(); NUM_PIXELS];
+
+    let mut group = c.benchmark_group("rgba_from_f32");
+    group.bench_function("slice_from_f32", |b| {
+        b.iter(|| Rgba::slice_from_f32(black_box(&src), black_box(&mut dst)))
+    });
+    group.bench_function("per_pixel", |b| {
+        b.iter(|| {
+            for (s, d) in black_box(&src).iter().zip(black_box(&mut dst)) {
+                *d = Rgba::from_f32(s[0], s[1], s[2], s[3]);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, to_f32, from_f32);
+criterion_main!(benches);
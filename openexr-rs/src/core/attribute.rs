@@ -7,9 +7,12 @@ use crate::{
             CppString, CppVectorFloat, CppVectorFloatRef, CppVectorFloatRefMut,
             CppVectorString, CppVectorStringRef, CppVectorStringRefMut,
         },
+        keycode::KeyCode,
         preview_image::{PreviewImage, PreviewImageRef, PreviewImageRefMut},
+        rational::Rational,
         refptr::{OpaquePtr, Ref, RefMut},
         tile_description::TileDescription,
+        timecode::{TimeCode, TimeCodePacking},
         Chromaticities, Compression, Envmap, LineOrder,
     },
     deep::DeepImageState,
@@ -19,9 +22,52 @@ use imath_traits::{Bound2, Matrix33, Matrix44, Vec2, Vec3};
 
 use std::ffi::CStr;
 
+/// An attribute of any type.
+///
+/// This is what is returned when iterating over the attributes in a
+/// [`Header`](crate::core::header::Header), where the type is not known ahead
+/// of time. Use [`type_name()`](Attribute::type_name) to find out what type
+/// it is.
+///
 #[repr(transparent)]
 pub struct Attribute(pub(crate) *mut sys::Imf_Attribute_t);
 
+unsafe impl OpaquePtr for Attribute {
+    type SysPointee = sys::Imf_Attribute_t;
+    type Pointee = Attribute;
+}
+
+pub type AttributeRef<'a, P = Attribute> = Ref<'a, P>;
+pub type AttributeRefMut<'a, P = Attribute> = RefMut<'a, P>;
+
+impl Attribute {
+    /// Get this attribute's type name
+    pub fn type_name(&self) -> &str {
+        unsafe {
+            let mut ptr = std::ptr::null();
+            sys::Imf_Attribute_typeName(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            std::ffi::CStr::from_ptr(ptr)
+                .to_str()
+                .expect("Invalid UTF-8")
+        }
+    }
+}
+
+impl TypedAttribute for Attribute {
+    fn as_attribute_ptr(&self) -> *const sys::Imf_Attribute_t {
+        self.0
+    }
+}
+
+/// Implemented by every attribute type so that it can be inserted into a
+/// [`Header`](crate::core::header::Header).
+///
+/// The trait is object safe, so attributes of different types can be kept
+/// together as `Box<dyn TypedAttribute>` and inserted with
+/// [`Header::insert_dyn()`](crate::core::header::Header::insert_dyn).
+///
 pub trait TypedAttribute {
     fn as_attribute_ptr(&self) -> *const sys::Imf_Attribute_t;
 }
@@ -780,7 +826,21 @@ pub type CppVectorFloatAttributeRef<'a, P = CppVectorFloatAttribute> =
 pub type CppVectorFloatAttributeRefMut<'a, P = CppVectorFloatAttribute> =
     RefMut<'a, P>;
 
+/// The `floatvector` attribute, e.g. for per-frame curves or spectra
+///
+pub type FloatVectorAttribute = CppVectorFloatAttribute;
+
 impl CppVectorFloatAttribute {
+    /// Create a new attribute holding a copy of `values`
+    pub fn new(values: &[f32]) -> CppVectorFloatAttribute {
+        CppVectorFloatAttribute::from_value(&CppVectorFloat::from_slice(values))
+    }
+
+    /// Get a copy of the contained floats
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.value().as_slice().to_vec()
+    }
+
     /// Create a new attribute wrapping the given value
     pub fn from_value(value: &CppVectorFloat) -> CppVectorFloatAttribute {
         let mut inner = std::ptr::null_mut();
@@ -926,13 +986,11 @@ impl CppStringAttribute {
     /// Create a new attribute wrapping the given value
     pub fn from_value(value: &str) -> CppStringAttribute {
         let mut inner = std::ptr::null_mut();
-        unsafe {
-            let s = CppString::new(value);
-
+        CppString::with_scratch(value, |s| unsafe {
             sys::Imf_CppStringAttribute_from_value(&mut inner, s.0)
                 .into_result()
                 .unwrap();
-        }
+        });
 
         CppStringAttribute(inner)
     }
@@ -971,6 +1029,78 @@ impl TypedAttribute for CppStringAttribute {
     }
 }
 
+// ----------------------------------------------------------------------------
+// KeyCodeAttribute
+#[repr(transparent)]
+pub struct KeyCodeAttribute(pub(crate) *mut sys::Imf_KeyCodeAttribute_t);
+
+unsafe impl OpaquePtr for KeyCodeAttribute {
+    type SysPointee = sys::Imf_KeyCodeAttribute_t;
+    type Pointee = KeyCodeAttribute;
+}
+
+pub type KeyCodeAttributeRef<'a, P = KeyCodeAttribute> = Ref<'a, P>;
+pub type KeyCodeAttributeRefMut<'a, P = KeyCodeAttribute> = RefMut<'a, P>;
+
+impl KeyCodeAttribute {
+    /// Create a new attribute wrapping the given value
+    pub fn from_value(value: &KeyCode) -> KeyCodeAttribute {
+        let mut inner = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_KeyCodeAttribute_from_value(
+                &mut inner,
+                value as *const KeyCode as *const sys::Imf_KeyCode_t,
+            )
+            .into_result()
+            .unwrap();
+        }
+
+        KeyCodeAttribute(inner)
+    }
+
+    /// Access to the contained value
+    pub fn value(&self) -> &KeyCode {
+        let mut ptr = std::ptr::null();
+        unsafe {
+            sys::Imf_KeyCodeAttribute_value_const(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            &*(ptr as *const KeyCode)
+        }
+    }
+
+    /// Mutable access to the contained value
+    pub fn value_mut(&mut self) -> &mut KeyCode {
+        let mut ptr = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_KeyCodeAttribute_value(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            &mut *(ptr as *mut KeyCode)
+        }
+    }
+
+    /// Get this attribute's type name
+    pub fn type_name(&self) -> &str {
+        unsafe {
+            let mut ptr = std::ptr::null();
+            sys::Imf_KeyCodeAttribute_typeName(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            std::ffi::CStr::from_ptr(ptr)
+                .to_str()
+                .expect("Invalid UTF-8")
+        }
+    }
+}
+
+impl TypedAttribute for KeyCodeAttribute {
+    fn as_attribute_ptr(&self) -> *const sys::Imf_Attribute_t {
+        self.0 as *const sys::Imf_KeyCodeAttribute_t
+            as *const sys::Imf_Attribute_t
+    }
+}
+
 // ----------------------------------------------------------------------------
 // LineOrderAttribute
 #[repr(transparent)]
@@ -1057,6 +1187,27 @@ pub type M33fAttributeRef<'a, P = M33fAttribute> = Ref<'a, P>;
 pub type M33fAttributeRefMut<'a, P = M33fAttribute> = RefMut<'a, P>;
 
 impl M33fAttribute {
+    /// Create a new attribute holding the matrix `rows`, where `rows[i][j]`
+    /// is element `[i][j]` of the Imath `M33f`
+    pub fn new(rows: [[f32; 3]; 3]) -> M33fAttribute {
+        let mut flat = [0.0f32; 9];
+        for (dst, src) in flat.chunks_exact_mut(3).zip(rows.iter()) {
+            dst.copy_from_slice(src);
+        }
+        M33fAttribute::from_value(&flat)
+    }
+
+    /// Get the contained matrix as rows, where `rows[i][j]` is element
+    /// `[i][j]` of the Imath `M33f`
+    pub fn rows(&self) -> [[f32; 3]; 3] {
+        let flat = self.value::<[f32; 9]>();
+        let mut rows = [[0.0f32; 3]; 3];
+        for (dst, src) in rows.iter_mut().zip(flat.chunks_exact(3)) {
+            dst.copy_from_slice(src);
+        }
+        rows
+    }
+
     /// Create a new attribute wrapping the given value
     pub fn from_value<T>(value: &T) -> M33fAttribute
     where
@@ -1117,6 +1268,12 @@ impl M33fAttribute {
     }
 }
 
+impl TypedAttribute for M33fAttribute {
+    fn as_attribute_ptr(&self) -> *const sys::Imf_Attribute_t {
+        self.0 as *const sys::Imf_M33fAttribute_t as *const sys::Imf_Attribute_t
+    }
+}
+
 // ----------------------------------------------------------------------------
 // M33dAttribute
 #[repr(transparent)]
@@ -1429,6 +1586,78 @@ impl TypedAttribute for PreviewImageAttribute {
     }
 }
 
+// ----------------------------------------------------------------------------
+// RationalAttribute
+#[repr(transparent)]
+pub struct RationalAttribute(pub(crate) *mut sys::Imf_RationalAttribute_t);
+
+unsafe impl OpaquePtr for RationalAttribute {
+    type SysPointee = sys::Imf_RationalAttribute_t;
+    type Pointee = RationalAttribute;
+}
+
+pub type RationalAttributeRef<'a, P = RationalAttribute> = Ref<'a, P>;
+pub type RationalAttributeRefMut<'a, P = RationalAttribute> = RefMut<'a, P>;
+
+impl RationalAttribute {
+    /// Create a new attribute wrapping the given value
+    pub fn from_value(value: &Rational) -> RationalAttribute {
+        let mut inner = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_RationalAttribute_from_value(
+                &mut inner,
+                value as *const Rational as *const sys::Imf_Rational_t,
+            )
+            .into_result()
+            .unwrap();
+        }
+
+        RationalAttribute(inner)
+    }
+
+    /// Access to the contained value
+    pub fn value(&self) -> Rational {
+        let mut ptr = std::ptr::null();
+        unsafe {
+            sys::Imf_RationalAttribute_value_const(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            *(ptr as *const Rational)
+        }
+    }
+
+    /// Mutable access to the contained value
+    pub fn value_mut(&mut self) -> &mut Rational {
+        let mut ptr = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_RationalAttribute_value(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            &mut *(ptr as *mut Rational)
+        }
+    }
+
+    /// Get this attribute's type name
+    pub fn type_name(&self) -> &str {
+        unsafe {
+            let mut ptr = std::ptr::null();
+            sys::Imf_RationalAttribute_typeName(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            std::ffi::CStr::from_ptr(ptr)
+                .to_str()
+                .expect("Invalid UTF-8")
+        }
+    }
+}
+
+impl TypedAttribute for RationalAttribute {
+    fn as_attribute_ptr(&self) -> *const sys::Imf_Attribute_t {
+        self.0 as *const sys::Imf_RationalAttribute_t
+            as *const sys::Imf_Attribute_t
+    }
+}
+
 // ----------------------------------------------------------------------------
 // TileDescriptionAttribute
 #[repr(transparent)]
@@ -1505,6 +1734,78 @@ impl TypedAttribute for TileDescriptionAttribute {
     }
 }
 
+// ----------------------------------------------------------------------------
+// TimeCodeAttribute
+#[repr(transparent)]
+pub struct TimeCodeAttribute(pub(crate) *mut sys::Imf_TimeCodeAttribute_t);
+
+unsafe impl OpaquePtr for TimeCodeAttribute {
+    type SysPointee = sys::Imf_TimeCodeAttribute_t;
+    type Pointee = TimeCodeAttribute;
+}
+
+pub type TimeCodeAttributeRef<'a, P = TimeCodeAttribute> = Ref<'a, P>;
+pub type TimeCodeAttributeRefMut<'a, P = TimeCodeAttribute> = RefMut<'a, P>;
+
+impl TimeCodeAttribute {
+    /// Create a new attribute wrapping the given value
+    pub fn from_value(value: &TimeCode) -> TimeCodeAttribute {
+        let mut inner = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_TimeCodeAttribute_from_value(
+                &mut inner,
+                value as *const TimeCode as *const sys::Imf_TimeCode_t,
+            )
+            .into_result()
+            .unwrap();
+        }
+
+        TimeCodeAttribute(inner)
+    }
+
+    /// Access to the contained value
+    pub fn value(&self) -> &TimeCode {
+        let mut ptr = std::ptr::null();
+        unsafe {
+            sys::Imf_TimeCodeAttribute_value_const(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            &*(ptr as *const TimeCode)
+        }
+    }
+
+    /// Mutable access to the contained value
+    pub fn value_mut(&mut self) -> &mut TimeCode {
+        let mut ptr = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_TimeCodeAttribute_value(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            &mut *(ptr as *mut TimeCode)
+        }
+    }
+
+    /// Get this attribute's type name
+    pub fn type_name(&self) -> &str {
+        unsafe {
+            let mut ptr = std::ptr::null();
+            sys::Imf_TimeCodeAttribute_typeName(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            std::ffi::CStr::from_ptr(ptr)
+                .to_str()
+                .expect("Invalid UTF-8")
+        }
+    }
+}
+
+impl TypedAttribute for TimeCodeAttribute {
+    fn as_attribute_ptr(&self) -> *const sys::Imf_Attribute_t {
+        self.0 as *const sys::Imf_TimeCodeAttribute_t
+            as *const sys::Imf_Attribute_t
+    }
+}
+
 // ----------------------------------------------------------------------------
 // V2iAttribute
 #[repr(transparent)]
@@ -1839,6 +2140,11 @@ pub type V3fAttributeRef<'a, P = V3fAttribute> = Ref<'a, P>;
 pub type V3fAttributeRefMut<'a, P = V3fAttribute> = RefMut<'a, P>;
 
 impl V3fAttribute {
+    /// Create a new attribute holding the vector `value`
+    pub fn new(value: [f32; 3]) -> V3fAttribute {
+        V3fAttribute::from_value(&value)
+    }
+
     /// Create a new attribute wrapping the given value
     pub fn from_value<T>(value: &T) -> V3fAttribute
     where
@@ -1885,6 +2191,15 @@ impl V3fAttribute {
         }
     }
 
+    /// Set the contained value
+    pub fn set_value<T>(&mut self, value: &T)
+    where
+        T: Vec3<f32>,
+    {
+        self.value_mut::<[f32; 3]>()
+            .copy_from_slice(&value.as_slice()[..3]);
+    }
+
     /// Get this attribute's type name
     pub fn type_name(&self) -> &str {
         unsafe {
@@ -1984,3 +2299,299 @@ impl TypedAttribute for V3dAttribute {
         self.0 as *const sys::Imf_V3dAttribute_t as *const sys::Imf_Attribute_t
     }
 }
+
+// ----------------------------------------------------------------------------
+// AttributeValue
+
+/// A decoded copy of the value of an attribute, used by the generic attribute
+/// accessors on [`Header`](crate::core::header::Header) such as
+/// [`attribute_value()`](crate::core::header::Header::attribute_value) and
+/// [`set_attribute_value()`](crate::core::header::Header::set_attribute_value).
+///
+/// Vector, box and matrix types are stored as flat arrays in the same layout
+/// as their Imath counterparts.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    Int(i32),
+    Float(f32),
+    Double(f64),
+    String(String),
+    VectorFloat(Vec<f32>),
+    VectorString(Vec<String>),
+    Compression(Compression),
+    LineOrder(LineOrder),
+    Envmap(Envmap),
+    DeepImageState(DeepImageState),
+    V2i([i32; 2]),
+    V2f([f32; 2]),
+    V2d([f64; 2]),
+    V3i([i32; 3]),
+    V3f([f32; 3]),
+    V3d([f64; 3]),
+    Box2i([i32; 4]),
+    Box2f([f32; 4]),
+    M33f([f32; 9]),
+    M33d([f64; 9]),
+    M44f([f32; 16]),
+    M44d([f64; 16]),
+    TimeCode(TimeCode),
+    KeyCode(KeyCode),
+}
+
+impl AttributeValue {
+    /// Get the OpenEXR type name of the attribute this value would be stored
+    /// as, e.g. `"int"` or `"v2f"`
+    ///
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            AttributeValue::Int(_) => "int",
+            AttributeValue::Float(_) => "float",
+            AttributeValue::Double(_) => "double",
+            AttributeValue::String(_) => "string",
+            AttributeValue::VectorFloat(_) => "floatvector",
+            AttributeValue::VectorString(_) => "stringvector",
+            AttributeValue::Compression(_) => "compression",
+            AttributeValue::LineOrder(_) => "lineOrder",
+            AttributeValue::Envmap(_) => "envmap",
+            AttributeValue::DeepImageState(_) => "deepImageState",
+            AttributeValue::V2i(_) => "v2i",
+            AttributeValue::V2f(_) => "v2f",
+            AttributeValue::V2d(_) => "v2d",
+            AttributeValue::V3i(_) => "v3i",
+            AttributeValue::V3f(_) => "v3f",
+            AttributeValue::V3d(_) => "v3d",
+            AttributeValue::Box2i(_) => "box2i",
+            AttributeValue::Box2f(_) => "box2f",
+            AttributeValue::M33f(_) => "m33f",
+            AttributeValue::M33d(_) => "m33d",
+            AttributeValue::M44f(_) => "m44f",
+            AttributeValue::M44d(_) => "m44d",
+            AttributeValue::TimeCode(_) => "timecode",
+            AttributeValue::KeyCode(_) => "keycode",
+        }
+    }
+
+    /// Compare two values, treating floating-point values as equal only if
+    /// their bit patterns are, so that unlike `==` every value, including
+    /// NaN, is equal to itself
+    ///
+    pub(crate) fn bitwise_eq(&self, other: &AttributeValue) -> bool {
+        fn f32s(a: &[f32], b: &[f32]) -> bool {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| a.to_bits() == b.to_bits())
+        }
+        fn f64s(a: &[f64], b: &[f64]) -> bool {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| a.to_bits() == b.to_bits())
+        }
+
+        use AttributeValue as V;
+        match (self, other) {
+            (V::Float(a), V::Float(b)) => a.to_bits() == b.to_bits(),
+            (V::Double(a), V::Double(b)) => a.to_bits() == b.to_bits(),
+            (V::VectorFloat(a), V::VectorFloat(b)) => f32s(a, b),
+            (V::V2f(a), V::V2f(b)) => f32s(a, b),
+            (V::V3f(a), V::V3f(b)) => f32s(a, b),
+            (V::Box2f(a), V::Box2f(b)) => f32s(a, b),
+            (V::M33f(a), V::M33f(b)) => f32s(a, b),
+            (V::M44f(a), V::M44f(b)) => f32s(a, b),
+            (V::V2d(a), V::V2d(b)) => f64s(a, b),
+            (V::V3d(a), V::V3d(b)) => f64s(a, b),
+            (V::M33d(a), V::M33d(b)) => f64s(a, b),
+            (V::M44d(a), V::M44d(b)) => f64s(a, b),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl std::hash::Hash for AttributeValue {
+    /// Floating-point values are hashed by their bit pattern, with `-0.0`
+    /// hashed the same as `0.0` so that values that compare equal hash equal.
+    ///
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.type_name().hash(state);
+        match self {
+            AttributeValue::Int(v) => v.hash(state),
+            AttributeValue::Float(v) => hash_f32(*v, state),
+            AttributeValue::Double(v) => hash_f64(*v, state),
+            AttributeValue::String(v) => v.hash(state),
+            AttributeValue::VectorFloat(v) => {
+                v.len().hash(state);
+                v.iter().for_each(|v| hash_f32(*v, state));
+            }
+            AttributeValue::VectorString(v) => v.hash(state),
+            AttributeValue::Compression(v) => (*v as i32).hash(state),
+            AttributeValue::LineOrder(v) => (*v as i32).hash(state),
+            AttributeValue::Envmap(v) => (*v as i32).hash(state),
+            AttributeValue::DeepImageState(v) => (*v as i32).hash(state),
+            AttributeValue::V2i(v) => v.hash(state),
+            AttributeValue::V3i(v) => v.hash(state),
+            AttributeValue::Box2i(v) => v.hash(state),
+            AttributeValue::V2f(v) => {
+                v.iter().for_each(|v| hash_f32(*v, state))
+            }
+            AttributeValue::V3f(v) => {
+                v.iter().for_each(|v| hash_f32(*v, state))
+            }
+            AttributeValue::Box2f(v) => {
+                v.iter().for_each(|v| hash_f32(*v, state))
+            }
+            AttributeValue::M33f(v) => {
+                v.iter().for_each(|v| hash_f32(*v, state))
+            }
+            AttributeValue::M44f(v) => {
+                v.iter().for_each(|v| hash_f32(*v, state))
+            }
+            AttributeValue::V2d(v) => {
+                v.iter().for_each(|v| hash_f64(*v, state))
+            }
+            AttributeValue::V3d(v) => {
+                v.iter().for_each(|v| hash_f64(*v, state))
+            }
+            AttributeValue::M33d(v) => {
+                v.iter().for_each(|v| hash_f64(*v, state))
+            }
+            AttributeValue::M44d(v) => {
+                v.iter().for_each(|v| hash_f64(*v, state))
+            }
+            AttributeValue::TimeCode(v) => {
+                v.time_and_flags(TimeCodePacking::Tv60).hash(state);
+                v.user_data().hash(state);
+            }
+            AttributeValue::KeyCode(v) => [
+                v.film_mfc_code(),
+                v.film_type(),
+                v.prefix(),
+                v.count(),
+                v.perf_offset(),
+                v.perfs_per_frame(),
+                v.perfs_per_count(),
+            ]
+            .hash(state),
+        }
+    }
+}
+
+impl std::fmt::Display for AttributeValue {
+    /// Format the value for display, e.g. `"(0, 0) - (63, 63)"` for a
+    /// [`AttributeValue::Box2i`]. Enumerations are written as their variant
+    /// name and all other vector and matrix types as a flat list.
+    ///
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeValue::Int(v) => write!(f, "{}", v),
+            AttributeValue::Float(v) => write!(f, "{}", v),
+            AttributeValue::Double(v) => write!(f, "{}", v),
+            AttributeValue::String(v) => write!(f, "{}", v),
+            AttributeValue::VectorFloat(v) => write!(f, "{:?}", v),
+            AttributeValue::VectorString(v) => write!(f, "{}", v.join(", ")),
+            AttributeValue::Compression(v) => write!(f, "{:?}", v),
+            AttributeValue::LineOrder(v) => write!(f, "{:?}", v),
+            AttributeValue::Envmap(v) => write!(f, "{:?}", v),
+            AttributeValue::DeepImageState(v) => write!(f, "{:?}", v),
+            AttributeValue::V2i(v) => write!(f, "{:?}", v),
+            AttributeValue::V2f(v) => write!(f, "{:?}", v),
+            AttributeValue::V2d(v) => write!(f, "{:?}", v),
+            AttributeValue::V3i(v) => write!(f, "{:?}", v),
+            AttributeValue::V3f(v) => write!(f, "{:?}", v),
+            AttributeValue::V3d(v) => write!(f, "{:?}", v),
+            AttributeValue::Box2i(v) => {
+                write!(f, "({}, {}) - ({}, {})", v[0], v[1], v[2], v[3])
+            }
+            AttributeValue::Box2f(v) => {
+                write!(f, "({}, {}) - ({}, {})", v[0], v[1], v[2], v[3])
+            }
+            AttributeValue::M33f(v) => write!(f, "{:?}", v),
+            AttributeValue::M33d(v) => write!(f, "{:?}", v),
+            AttributeValue::M44f(v) => write!(f, "{:?}", v),
+            AttributeValue::M44d(v) => write!(f, "{:?}", v),
+            AttributeValue::TimeCode(v) => write!(
+                f,
+                "{:02}:{:02}:{:02}:{:02}",
+                v.hours(),
+                v.minutes(),
+                v.seconds(),
+                v.frame()
+            ),
+            AttributeValue::KeyCode(v) => write!(
+                f,
+                "{:02} {:02} {:06} {:04} {:03}, {} perfs per frame, {} perfs \
+                per count",
+                v.film_mfc_code(),
+                v.film_type(),
+                v.prefix(),
+                v.count(),
+                v.perf_offset(),
+                v.perfs_per_frame(),
+                v.perfs_per_count()
+            ),
+        }
+    }
+}
+
+pub(crate) fn hash_f32<H: std::hash::Hasher>(v: f32, state: &mut H) {
+    use std::hash::Hash;
+    // 0.0 == -0.0, so they must hash the same
+    (if v == 0.0 { 0 } else { v.to_bits() }).hash(state);
+}
+
+pub(crate) fn hash_f64<H: std::hash::Hasher>(v: f64, state: &mut H) {
+    use std::hash::Hash;
+    (if v == 0.0 { 0 } else { v.to_bits() }).hash(state);
+}
+
+/// A single difference between the attributes of two headers, as returned by
+/// [`Header::diff()`](crate::core::header::Header::diff).
+///
+/// Values are `None` if the attribute's type cannot be represented by
+/// [`AttributeValue`], e.g. channel lists or preview images.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeDiff {
+    /// The attribute only exists in the other header
+    Added(String, Option<AttributeValue>),
+    /// The attribute only exists in this header
+    Removed(String, Option<AttributeValue>),
+    /// The attribute exists in both headers with different values. The
+    /// values are this header's then the other header's.
+    Changed(String, Option<AttributeValue>, Option<AttributeValue>),
+}
+
+impl AttributeDiff {
+    /// Get the name of the attribute that differs
+    ///
+    pub fn name(&self) -> &str {
+        match self {
+            AttributeDiff::Added(name, _)
+            | AttributeDiff::Removed(name, _)
+            | AttributeDiff::Changed(name, _, _) => name,
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn attribute_value_hash1() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let hash = |v: &AttributeValue| {
+        let mut hasher = DefaultHasher::new();
+        v.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    assert_eq!(
+        hash(&AttributeValue::Float(0.0)),
+        hash(&AttributeValue::Float(-0.0))
+    );
+    assert_eq!(
+        hash(&AttributeValue::V2f([1.0, 2.0])),
+        hash(&AttributeValue::V2f([1.0, 2.0]))
+    );
+    assert_ne!(
+        hash(&AttributeValue::Float(1.0)),
+        hash(&AttributeValue::Double(1.0))
+    );
+}
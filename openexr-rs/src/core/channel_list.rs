@@ -3,12 +3,17 @@ pub use sys::Imf_Channel_t as Channel;
 
 use crate::core::{
     cppstd::CppString,
+    error::Error,
     refptr::{OpaquePtr, Ref, RefMut},
+    PixelType,
 };
 
+use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::marker::PhantomData;
 
+type Result<T, E = Error> = std::result::Result<T, E>;
+
 #[repr(transparent)]
 pub struct ChannelList(pub(crate) *mut sys::Imf_ChannelList_t);
 
@@ -42,6 +47,157 @@ pub const CHANNEL_UINT: Channel = Channel {
     p_linear: true,
 };
 
+/// Builds a [`Channel`] without having to spell out every field.
+///
+/// The defaults are a sampling of 1 in x and y and `p_linear` set to `false`.
+///
+/// `p_linear` is a hint that the values of the channel are perceptually
+/// linear, i.e. that equal differences in value look like equal differences
+/// in brightness, as with values that have already been log or gamma encoded.
+/// Lossy compression methods such as DWAA and DWAB use it to decide how to
+/// quantize the channel.
+///
+/// ```no_run
+/// use openexr::core::{channel_list::ChannelBuilder, PixelType};
+///
+/// let depth = ChannelBuilder::new(PixelType::Float).p_linear(true).build();
+/// ```
+///
+pub struct ChannelBuilder {
+    pixel_type: PixelType,
+    x_sampling: i32,
+    y_sampling: i32,
+    p_linear: bool,
+}
+
+impl ChannelBuilder {
+    /// Start building a channel that stores `pixel_type` values
+    ///
+    pub fn new(pixel_type: PixelType) -> ChannelBuilder {
+        ChannelBuilder {
+            pixel_type,
+            x_sampling: 1,
+            y_sampling: 1,
+            p_linear: false,
+        }
+    }
+
+    pub fn x_sampling(mut self, x: i32) -> Self {
+        self.x_sampling = x;
+        self
+    }
+
+    pub fn y_sampling(mut self, y: i32) -> Self {
+        self.y_sampling = y;
+        self
+    }
+
+    pub fn p_linear(mut self, p_linear: bool) -> Self {
+        self.p_linear = p_linear;
+        self
+    }
+
+    pub fn build(self) -> Channel {
+        Channel {
+            type_: self.pixel_type.into(),
+            x_sampling: self.x_sampling,
+            y_sampling: self.y_sampling,
+            p_linear: self.p_linear,
+        }
+    }
+}
+
+/// Get the name OpenEXR uses for `pixel_type`: `"uint"`, `"half"` or
+/// `"float"`.
+///
+pub fn pixel_type_name(pixel_type: PixelType) -> &'static str {
+    match pixel_type {
+        PixelType::Uint => "uint",
+        PixelType::Half => "half",
+        PixelType::Float => "float",
+        _ => "unknown",
+    }
+}
+
+/// Wraps a [`Channel`] so that it prints with its pixel type name rather
+/// than the raw enum value, e.g. for header dumps.
+///
+/// ```no_run
+/// use openexr::core::channel_list::{ChannelDisplay, CHANNEL_HALF};
+///
+/// // prints "half, sampling 1x1, pLinear"
+/// println!("{}", ChannelDisplay(&CHANNEL_HALF));
+/// ```
+///
+pub struct ChannelDisplay<'a>(pub &'a Channel);
+
+impl<'a> std::fmt::Debug for ChannelDisplay<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Channel")
+            .field(
+                "type",
+                &format_args!("{}", pixel_type_name(self.0.type_.into())),
+            )
+            .field("x_sampling", &self.0.x_sampling)
+            .field("y_sampling", &self.0.y_sampling)
+            .field("p_linear", &self.0.p_linear)
+            .finish()
+    }
+}
+
+impl<'a> std::fmt::Display for ChannelDisplay<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, sampling {}x{}",
+            pixel_type_name(self.0.type_.into()),
+            self.0.x_sampling,
+            self.0.y_sampling
+        )?;
+        if self.0.p_linear {
+            write!(f, ", pLinear")?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of channels of each pixel type in a [`ChannelList`], as
+/// returned by [`ChannelList::pixel_type_counts()`]
+///
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PixelTypeCounts {
+    pub uint: usize,
+    pub half: usize,
+    pub float: usize,
+}
+
+/// What [`ChannelList::merge()`] does with a channel that is in both lists
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the channel already in the list
+    KeepExisting,
+    /// Replace the channel already in the list with the incoming one
+    Overwrite,
+    /// Fail if the two channels have different pixel types or sampling,
+    /// otherwise keep the existing channel
+    Error,
+}
+
+/// Common alternative spellings of the standard channel names, mapped to the
+/// names OpenEXR uses, for use with [`ChannelList::renamed()`]
+///
+pub const STANDARD_CHANNEL_ALIASES: &[(&str, &str)] = &[
+    ("luminance", "Y"),
+    ("Luminance", "Y"),
+    ("alpha", "A"),
+    ("Alpha", "A"),
+    ("red", "R"),
+    ("green", "G"),
+    ("blue", "B"),
+    ("depth", "Z"),
+];
+
 unsafe impl OpaquePtr for ChannelList {
     type SysPointee = sys::Imf_ChannelList_t;
     type Pointee = ChannelList;
@@ -68,6 +224,107 @@ impl ChannelList {
         }
     }
 
+    /// Insert each of `base_channels` into the layer `layer`.
+    ///
+    /// Each channel is named `layer + "." + base_name`, so inserting `R`, `G`
+    /// and `B` into the layer `"specular"` gives `specular.R`, `specular.G`
+    /// and `specular.B`. If `layer` is empty the base names are used as-is.
+    ///
+    pub fn insert_layer(
+        &mut self,
+        layer: &str,
+        base_channels: &[(&str, Channel)],
+    ) {
+        for (base_name, channel) in base_channels {
+            if layer.is_empty() {
+                self.insert(base_name, channel);
+            } else {
+                self.insert(&format!("{}.{}", layer, base_name), channel);
+            }
+        }
+    }
+
+    /// Insert every channel of `other` into this list, resolving channels
+    /// present in both according to `on_conflict`.
+    ///
+    /// This is useful when combining the AOVs of several sources into one
+    /// image.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidType`] - if `on_conflict` is
+    /// [`ConflictPolicy::Error`] and a channel in both lists has a different
+    /// pixel type or sampling in each. Nothing is inserted in this case.
+    ///
+    pub fn merge(
+        &mut self,
+        other: &ChannelList,
+        on_conflict: ConflictPolicy,
+    ) -> Result<()> {
+        if on_conflict == ConflictPolicy::Error {
+            for (name, incoming) in other.iter() {
+                if let Some(existing) = self.get(name) {
+                    if existing.type_ != incoming.type_
+                        || existing.x_sampling != incoming.x_sampling
+                        || existing.y_sampling != incoming.y_sampling
+                    {
+                        return Err(Error::InvalidType(format!(
+                            "channel \"{}\" is {} in one list and {} in \
+                            the other",
+                            name,
+                            ChannelDisplay(existing),
+                            ChannelDisplay(incoming)
+                        )));
+                    }
+                }
+            }
+        }
+
+        for (name, incoming) in other.iter() {
+            if on_conflict == ConflictPolicy::Overwrite
+                || self.get(name).is_none()
+            {
+                self.insert(name, incoming);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get a copy of this list with channels renamed according to `map`, a
+    /// list of `(from, to)` pairs.
+    ///
+    /// Entries whose `from` channel does not exist are skipped. The new list
+    /// is sorted by the new names as usual.
+    ///
+    /// OpenEXR cannot remove channels from a list, so this builds a new one.
+    /// Use [`Header::canonicalize_channel_names()`](crate::core::header::Header::canonicalize_channel_names)
+    /// to rename the channels of a header in place.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if several channels would end up with
+    /// the same name, e.g. `"alpha"` and `"Alpha"` both renamed to `"A"`, or
+    /// `"alpha"` renamed to `"A"` when there is already an `"A"`
+    ///
+    pub fn renamed(&self, map: &[(&str, &str)]) -> Result<ChannelList> {
+        let mut list = ChannelList::new();
+        let mut origins = HashMap::new();
+        for (name, channel) in self.iter() {
+            let new_name = map
+                .iter()
+                .find(|(from, _)| *from == name)
+                .map_or(name, |(_, to)| *to);
+            if let Some(other) = origins.insert(new_name, name) {
+                return Err(Error::InvalidArgument(format!(
+                    "channels \"{}\" and \"{}\" would both be renamed to \
+                    \"{}\"",
+                    other, name, new_name
+                )));
+            }
+            list.insert(new_name, channel);
+        }
+        Ok(list)
+    }
+
     /// Get a reference to a channel by name.
     ///
     /// # Returns
@@ -94,6 +351,18 @@ impl ChannelList {
         }
     }
 
+    /// Get the `p_linear` flag of the channel called `name`.
+    ///
+    /// See [`ChannelBuilder`] for what the flag means.
+    ///
+    /// # Returns
+    /// * `Some(bool)` - if the channel called `name` exists
+    /// * `None` - otherwise
+    ///
+    pub fn p_linear(&self, name: &str) -> Option<bool> {
+        self.get(name).map(|c| c.p_linear)
+    }
+
     /// Get a mutable reference to a channel by name.
     ///
     /// # Returns
@@ -140,6 +409,109 @@ impl ChannelList {
             }
         }
     }
+
+    /// Count the channels of each pixel type, e.g. to see at a glance whether
+    /// an image mixes float depth with half color.
+    ///
+    pub fn pixel_type_counts(&self) -> PixelTypeCounts {
+        let mut counts = PixelTypeCounts::default();
+        for (_, channel) in self.iter() {
+            match PixelType::from(channel.type_) {
+                PixelType::Uint => counts.uint += 1,
+                PixelType::Half => counts.half += 1,
+                PixelType::Float => counts.float += 1,
+                _ => (),
+            }
+        }
+        counts
+    }
+
+    /// Check that every channel name will survive a round trip through other
+    /// applications.
+    ///
+    /// OpenEXR itself accepts any non-empty name, but many compositing and
+    /// paint packages mangle or reject names containing whitespace or
+    /// control characters. Names longer than 31 bytes are also flagged
+    /// unless `allow_long_names` is `true`, as they need the
+    /// [`VersionFlags::LONG_NAMES`](crate::core::version::VersionFlags::LONG_NAMES)
+    /// flag, which readers older than OpenEXR 1.7 do not understand.
+    ///
+    /// ## Errors
+    /// * [`Error::InvalidHeader`] - listing each offending name and why
+    ///
+    pub fn validate_names(&self, allow_long_names: bool) -> Result<()> {
+        const MAX_SHORT_NAME_LEN: usize = 31;
+
+        let mut problems = Vec::new();
+        for (name, _) in self.iter() {
+            if name.chars().any(char::is_control) {
+                problems.push(format!(
+                    "channel {:?} contains control characters",
+                    name
+                ));
+            } else if name.chars().any(char::is_whitespace) {
+                problems
+                    .push(format!("channel {:?} contains whitespace", name));
+            }
+            if !allow_long_names && name.len() > MAX_SHORT_NAME_LEN {
+                problems.push(format!(
+                    "channel {:?} is longer than {} bytes",
+                    name, MAX_SHORT_NAME_LEN
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidHeader(problems))
+        }
+    }
+
+    /// Get the first channel in the list.
+    ///
+    /// Channels are kept sorted by name, so this is the channel whose name
+    /// sorts first.
+    ///
+    /// # Returns
+    /// * `Some((name, &Channel))` - if the list is not empty
+    /// * `None` - otherwise
+    ///
+    pub fn first(&self) -> Option<(String, &Channel)> {
+        self.iter()
+            .next()
+            .map(|(name, chan)| (name.to_string(), chan))
+    }
+
+    /// Get the last channel in the list.
+    ///
+    /// Channels are kept sorted by name, so this is the channel whose name
+    /// sorts last.
+    ///
+    /// # Returns
+    /// * `Some((name, &Channel))` - if the list is not empty
+    /// * `None` - otherwise
+    ///
+    pub fn last(&self) -> Option<(String, &Channel)> {
+        self.iter()
+            .last()
+            .map(|(name, chan)| (name.to_string(), chan))
+    }
+
+    /// Get the number of channels in the list.
+    ///
+    /// OpenEXR does not expose the size of the underlying map, so this walks
+    /// the list, but it does so without copying any of the channel names.
+    ///
+    pub fn count(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Is the list empty?
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
 }
 
 impl ChannelList {
@@ -416,6 +788,35 @@ fn iter2() {
     }
 }
 
+#[cfg(test)]
+#[test]
+fn first_last() {
+    use crate::core::PixelType;
+    let mut list = ChannelList::new();
+    let channel = Channel {
+        type_: PixelType::Half.into(),
+        x_sampling: 1,
+        y_sampling: 1,
+        p_linear: true,
+    };
+
+    assert!(list.first().is_none());
+    assert!(list.last().is_none());
+    assert!(list.is_empty());
+    assert_eq!(list.count(), 0);
+
+    // insertion order should not matter as the list is sorted by name
+    list.insert("G", &channel);
+    list.insert("R", &channel);
+    list.insert("A", &channel);
+    list.insert("B", &channel);
+
+    assert_eq!(list.first().map(|(name, _)| name).unwrap(), "A");
+    assert_eq!(list.last().map(|(name, _)| name).unwrap(), "R");
+    assert!(!list.is_empty());
+    assert_eq!(list.count(), 4);
+}
+
 #[cfg(test)]
 #[test]
 fn eq() {
@@ -545,3 +946,244 @@ fn channels_with_prefix() {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+#[test]
+fn insert_layer() {
+    use crate::core::PixelType;
+    let mut list = ChannelList::new();
+    let channel = Channel {
+        type_: PixelType::Half.into(),
+        x_sampling: 1,
+        y_sampling: 1,
+        p_linear: true,
+    };
+    let rgb = [
+        ("R", channel.clone()),
+        ("G", channel.clone()),
+        ("B", channel),
+    ];
+
+    list.insert_layer("specular", &rgb);
+    list.insert_layer("diffuse", &rgb);
+    list.insert_layer("", &rgb[..1]);
+
+    assert_eq!(
+        list.iter().map(|(name, _)| name).collect::<Vec<&str>>(),
+        [
+            "R",
+            "diffuse.B",
+            "diffuse.G",
+            "diffuse.R",
+            "specular.B",
+            "specular.G",
+            "specular.R"
+        ]
+    );
+    assert_eq!(list.layers(), ["diffuse", "specular"]);
+    assert_eq!(
+        list.channels_in_layer("specular")
+            .map(|(name, _)| name)
+            .collect::<Vec<&str>>(),
+        ["specular.B", "specular.G", "specular.R"]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn channel_builder_p_linear1() -> Result<(), crate::core::error::Error> {
+    use crate::core::{
+        frame_buffer::FrameBuffer, header::Header, input_file::InputFile,
+        output_file::OutputFile,
+    };
+
+    let color = ChannelBuilder::new(PixelType::Half).build();
+    assert!(!color.p_linear);
+    assert_eq!(color.x_sampling, 1);
+    let depth = ChannelBuilder::new(PixelType::Float).p_linear(true).build();
+
+    let (width, height) = (8, 8);
+    let mut header = Header::from_dimensions(width, height);
+    header.channels_mut().insert("R", &color);
+    header.channels_mut().insert("Z", &depth);
+    assert_eq!(header.channels().p_linear("R"), Some(false));
+    assert_eq!(header.channels().p_linear("Z"), Some(true));
+    assert_eq!(header.channels().p_linear("G"), None);
+
+    {
+        let data_window = [0, 0, width - 1, height - 1];
+        let mut r = vec![half::f16::ZERO; (width * height) as usize];
+        let mut z = vec![0.0f32; (width * height) as usize];
+        let mut frame_buffer = FrameBuffer::new();
+        unsafe {
+            frame_buffer.insert_channel_with_fill("R", &mut r, data_window, 0.0)
+        }?;
+        unsafe {
+            frame_buffer.insert_channel_with_fill("Z", &mut z, data_window, 0.0)
+        }?;
+
+        let mut file =
+            OutputFile::new("channel_builder_p_linear1.exr", &header, 1)?;
+        file.set_frame_buffer(&frame_buffer)?;
+        unsafe {
+            file.write_pixels(height)?;
+        }
+    }
+
+    let file = InputFile::new("channel_builder_p_linear1.exr", 1)?;
+    assert_eq!(file.header().channels().p_linear("R"), Some(false));
+    assert_eq!(file.header().channels().p_linear("Z"), Some(true));
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn channel_display1() {
+    use crate::core::PixelType;
+
+    assert_eq!(pixel_type_name(PixelType::Uint), "uint");
+    assert_eq!(pixel_type_name(PixelType::Half), "half");
+    assert_eq!(pixel_type_name(PixelType::Float), "float");
+
+    let channel = ChannelBuilder::new(PixelType::Float)
+        .x_sampling(2)
+        .y_sampling(2)
+        .build();
+    assert_eq!(
+        format!("{:?}", ChannelDisplay(&channel)),
+        "Channel { type: float, x_sampling: 2, y_sampling: 2, p_linear: false }"
+    );
+    assert_eq!(
+        format!("{}", ChannelDisplay(&channel)),
+        "float, sampling 2x2"
+    );
+    assert_eq!(
+        format!("{}", ChannelDisplay(&CHANNEL_HALF)),
+        "half, sampling 1x1, pLinear"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn channel_list_merge1() {
+    let mut beauty = ChannelList::new();
+    beauty.insert("R", &CHANNEL_HALF);
+    beauty.insert("Z", &CHANNEL_FLOAT);
+
+    let mut aov = ChannelList::new();
+    aov.insert("diffuse.R", &CHANNEL_HALF);
+    aov.insert("Z", &CHANNEL_HALF);
+
+    let mut keep = ChannelList::new();
+    keep.merge(&beauty, ConflictPolicy::Error).unwrap();
+    keep.merge(&aov, ConflictPolicy::KeepExisting).unwrap();
+    assert_eq!(keep.count(), 3);
+    assert!(keep.get("Z").unwrap().type_ == CHANNEL_FLOAT.type_);
+
+    let mut overwrite = ChannelList::new();
+    overwrite.merge(&beauty, ConflictPolicy::Error).unwrap();
+    overwrite.merge(&aov, ConflictPolicy::Overwrite).unwrap();
+    assert_eq!(overwrite.count(), 3);
+    assert!(overwrite.get("Z").unwrap().type_ == CHANNEL_HALF.type_);
+
+    let mut error = ChannelList::new();
+    error.merge(&beauty, ConflictPolicy::Error).unwrap();
+    match error.merge(&aov, ConflictPolicy::Error) {
+        Err(Error::InvalidType(msg)) => assert!(msg.contains("\"Z\"")),
+        r => panic!("expected InvalidType, got {:?}", r),
+    }
+    // nothing was inserted
+    assert!(error.get("diffuse.R").is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn channel_list_pixel_type_counts1() {
+    let mut list = ChannelList::new();
+    assert_eq!(list.pixel_type_counts(), PixelTypeCounts::default());
+
+    list.insert("R", &CHANNEL_HALF);
+    list.insert("G", &CHANNEL_HALF);
+    list.insert("B", &CHANNEL_HALF);
+    list.insert("Z", &CHANNEL_FLOAT);
+    list.insert("id", &CHANNEL_UINT);
+    assert_eq!(
+        list.pixel_type_counts(),
+        PixelTypeCounts {
+            uint: 1,
+            half: 3,
+            float: 1,
+        }
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn channel_list_renamed1() -> Result<()> {
+    let mut list = ChannelList::new();
+    list.insert("alpha", &CHANNEL_HALF);
+    list.insert("luminance", &CHANNEL_HALF);
+    list.insert("Z", &CHANNEL_FLOAT);
+
+    let renamed = list.renamed(&[("alpha", "A"), ("missing", "M")])?;
+    let names = renamed.iter().map(|(n, _)| n).collect::<Vec<_>>();
+    assert_eq!(names, vec!["A", "Z", "luminance"]);
+
+    let renamed = list.renamed(STANDARD_CHANNEL_ALIASES)?;
+    let names = renamed.iter().map(|(n, _)| n).collect::<Vec<_>>();
+    assert_eq!(names, vec!["A", "Y", "Z"]);
+    assert!(renamed.get("Z").unwrap().type_ == CHANNEL_FLOAT.type_);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn channel_list_renamed_collision1() {
+    let mut list = ChannelList::new();
+    list.insert("alpha", &CHANNEL_HALF);
+    list.insert("Alpha", &CHANNEL_HALF);
+
+    match list.renamed(&[("alpha", "A"), ("Alpha", "A")]) {
+        Err(Error::InvalidArgument(msg)) => {
+            assert!(msg.contains("\"alpha\"") && msg.contains("\"Alpha\""))
+        }
+        r => panic!("expected InvalidArgument, got {:?}", r.map(|_| ())),
+    }
+
+    // renaming onto a channel that is already there collides too
+    list.insert("A", &CHANNEL_FLOAT);
+    assert!(matches!(
+        list.renamed(&[("alpha", "A")]),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert_eq!(list.iter().count(), 3);
+}
+
+#[cfg(test)]
+#[test]
+fn channel_list_validate_names1() {
+    let mut list = ChannelList::new();
+    list.insert("R", &CHANNEL_HALF);
+    list.insert("diffuse.R", &CHANNEL_HALF);
+    assert_eq!(list.validate_names(false), Ok(()));
+
+    let long = "a".repeat(32);
+    list.insert("my channel", &CHANNEL_HALF);
+    list.insert("tab\there", &CHANNEL_HALF);
+    list.insert(&long, &CHANNEL_FLOAT);
+
+    match list.validate_names(false) {
+        Err(Error::InvalidHeader(problems)) => {
+            assert_eq!(problems.len(), 3, "{:?}", problems)
+        }
+        r => panic!("expected InvalidHeader, got {:?}", r),
+    }
+    match list.validate_names(true) {
+        Err(Error::InvalidHeader(problems)) => {
+            assert_eq!(problems.len(), 2, "{:?}", problems)
+        }
+        r => panic!("expected InvalidHeader, got {:?}", r),
+    }
+}
@@ -0,0 +1,147 @@
+//! Well-known [`Chromaticities`] and conversions derived from them.
+//!
+//! The CIE x,y coordinates of the primaries and white point describe the RGB
+//! color space of the pixels in an image. They are stored in the
+//! `"chromaticities"` attribute, and if it is absent readers should assume
+//! [`REC709`].
+//!
+
+use openexr_sys as sys;
+
+use crate::core::Chromaticities;
+
+const fn chromaticities(
+    red: [f32; 2],
+    green: [f32; 2],
+    blue: [f32; 2],
+    white: [f32; 2],
+) -> Chromaticities {
+    Chromaticities {
+        red: sys::Imath_V2f_t {
+            x: red[0],
+            y: red[1],
+        },
+        green: sys::Imath_V2f_t {
+            x: green[0],
+            y: green[1],
+        },
+        blue: sys::Imath_V2f_t {
+            x: blue[0],
+            y: blue[1],
+        },
+        white: sys::Imath_V2f_t {
+            x: white[0],
+            y: white[1],
+        },
+    }
+}
+
+const D65: [f32; 2] = [0.3127, 0.3290];
+const ACES_WHITE: [f32; 2] = [0.32168, 0.33767];
+
+/// ITU-R BT.709 primaries with a D65 white point. These are also the sRGB
+/// primaries, and the OpenEXR default.
+///
+pub const REC709: Chromaticities =
+    chromaticities([0.64, 0.33], [0.30, 0.60], [0.15, 0.06], D65);
+
+/// ITU-R BT.2020 primaries with a D65 white point
+///
+pub const REC2020: Chromaticities =
+    chromaticities([0.708, 0.292], [0.170, 0.797], [0.131, 0.046], D65);
+
+/// ACES AP0 primaries with the ACES white point, as used by ACES2065-1 and
+/// required for ACES container files
+///
+pub const ACES_AP0: Chromaticities =
+    chromaticities([0.7347, 0.2653], [0.0, 1.0], [0.0001, -0.0770], ACES_WHITE);
+
+/// ACES AP1 primaries with the ACES white point, as used by ACEScg and
+/// ACEScct
+///
+pub const ACES_AP1: Chromaticities =
+    chromaticities([0.713, 0.293], [0.165, 0.830], [0.128, 0.044], ACES_WHITE);
+
+/// DCI-P3 primaries with a D65 white point, as used by Display P3
+///
+pub const P3_D65: Chromaticities =
+    chromaticities([0.680, 0.320], [0.265, 0.690], [0.150, 0.060], D65);
+
+/// Compute the matrix that converts RGB values in the color space described by
+/// `chromaticities` to CIE XYZ, such that RGB (1, 1, 1) maps to the white point
+/// with a luminance Y of 1.
+///
+/// The matrix is stored row-major and multiplies column vectors, i.e.
+/// `xyz[i] = sum(m[i][j] * rgb[j])`, so the middle row holds the luminance
+/// weights of the primaries.
+///
+/// ```no_run
+/// use openexr::core::chromaticities::{to_xyz_matrix, REC709};
+///
+/// let m = to_xyz_matrix(&REC709);
+/// let luminance = |r: f32, g: f32, b: f32| m[1][0] * r + m[1][1] * g + m[1][2] * b;
+/// ```
+///
+pub fn to_xyz_matrix(chromaticities: &Chromaticities) -> [[f32; 3]; 3] {
+    let xyz = |v: &sys::Imath_V2f_t| {
+        let (x, y) = (v.x as f64, v.y as f64);
+        [x / y, 1.0, (1.0 - x - y) / y]
+    };
+
+    let r = xyz(&chromaticities.red);
+    let g = xyz(&chromaticities.green);
+    let b = xyz(&chromaticities.blue);
+    let w = xyz(&chromaticities.white);
+
+    // the columns are the XYZ of each primary, to be scaled so that they sum
+    // to the white point
+    let p = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+    let det = det3(&p);
+
+    let mut scale = [0.0f64; 3];
+    for (c, s) in scale.iter_mut().enumerate() {
+        let mut q = p;
+        for (row, wv) in q.iter_mut().zip(w.iter()) {
+            row[c] = *wv;
+        }
+        *s = det3(&q) / det;
+    }
+
+    let mut m = [[0.0f32; 3]; 3];
+    for (m_row, p_row) in m.iter_mut().zip(p.iter()) {
+        for ((mv, pv), s) in m_row.iter_mut().zip(p_row.iter()).zip(&scale) {
+            *mv = (pv * s) as f32;
+        }
+    }
+    m
+}
+
+fn det3(m: &[[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+#[cfg(test)]
+#[test]
+fn test_to_xyz_matrix() {
+    // IEC 61966-2-1 sRGB to XYZ
+    let reference = [
+        [0.4124, 0.3576, 0.1805],
+        [0.2126, 0.7152, 0.0722],
+        [0.0193, 0.1192, 0.9505],
+    ];
+
+    let m = to_xyz_matrix(&REC709);
+    for (m_row, r_row) in m.iter().zip(reference.iter()) {
+        for (mv, rv) in m_row.iter().zip(r_row.iter()) {
+            assert!((mv - rv).abs() < 1e-3, "{:?} != {:?}", m, reference);
+        }
+    }
+
+    // white always maps to Y = 1
+    for c in &[REC709, REC2020, ACES_AP0, ACES_AP1, P3_D65] {
+        let m = to_xyz_matrix(c);
+        assert!((m[1].iter().sum::<f32>() - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::core::{
+    header::Header, input_file::sample_counts, Compression, PixelType,
+};
+
+/// Describes the kind of data that will be stored in an image, so that a
+/// suitable [`Compression`] can be picked with [`recommended_for()`].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ContentHint {
+    /// Color images from photographic sources, e.g. scanned film or camera
+    /// plates, where a small loss of precision is acceptable.
+    PhotographicColor,
+    /// Color images produced by a renderer, which are typically noisy and
+    /// must be stored losslessly.
+    RenderedColor,
+    /// Non-color data such as depth, normals, motion vectors or IDs, which
+    /// must be stored losslessly and are often read a few scanlines at a time.
+    DataChannels,
+    /// Deep images. Only [`Compression::Zips`], [`Compression::Zip`] and
+    /// [`Compression::Rle`] are supported for deep data.
+    DeepData,
+}
+
+/// Get a sensible default [`Compression`] for the given kind of content.
+///
+/// * [`ContentHint::PhotographicColor`] - [`Compression::Dwaa`], lossy but
+/// visually transparent and very small.
+/// * [`ContentHint::RenderedColor`] - [`Compression::Piz`], lossless and
+/// handles grainy images well.
+/// * [`ContentHint::DataChannels`] - [`Compression::Zips`], lossless with
+/// one scanline per block for fast random access.
+/// * [`ContentHint::DeepData`] - [`Compression::Zips`], the best of the
+/// schemes supported for deep data.
+///
+/// ```no_run
+/// use openexr::core::Compression;
+/// use openexr::core::compression::{recommended_for, ContentHint};
+///
+/// assert_eq!(recommended_for(ContentHint::DataChannels), Compression::Zips);
+/// ```
+///
+pub fn recommended_for(content: ContentHint) -> Compression {
+    match content {
+        ContentHint::PhotographicColor => Compression::Dwaa,
+        ContentHint::RenderedColor => Compression::Piz,
+        ContentHint::DataChannels => Compression::Zips,
+        ContentHint::DeepData => Compression::Zips,
+    }
+}
+
+/// Get all the [`Compression`] methods supported by the bound OpenEXR
+/// library, in the order they are numbered in the file format.
+///
+/// ```no_run
+/// use openexr::core::compression;
+///
+/// for compression in compression::all() {
+///     println!("{:?}", compression);
+/// }
+/// ```
+///
+pub fn all() -> &'static [Compression] {
+    &[
+        Compression::No,
+        Compression::Rle,
+        Compression::Zips,
+        Compression::Zip,
+        Compression::Piz,
+        Compression::Pxr24,
+        Compression::B44,
+        Compression::B44a,
+        Compression::Dwaa,
+        Compression::Dwab,
+    ]
+}
+
+/// Get the number of scanlines that `compression` compresses together in one
+/// block, which is also the number of scanlines per chunk in a scanline
+/// file.
+///
+/// | Compression | Scanlines |
+/// |-------------|-----------|
+/// | No, Rle, Zips | 1 |
+/// | Zip, Pxr24 | 16 |
+/// | Piz, B44, B44a, Dwaa | 32 |
+/// | Dwab | 256 |
+///
+/// ```no_run
+/// use openexr::core::{compression::num_scanlines_per_block, Compression};
+///
+/// let height = 1080;
+/// let chunks = (height + num_scanlines_per_block(Compression::Dwab) - 1)
+///     / num_scanlines_per_block(Compression::Dwab);
+/// assert_eq!(chunks, 5);
+/// ```
+///
+pub fn num_scanlines_per_block(compression: Compression) -> usize {
+    match compression {
+        Compression::No | Compression::Rle | Compression::Zips => 1,
+        Compression::Zip | Compression::Pxr24 => 16,
+        Compression::Piz
+        | Compression::B44
+        | Compression::B44a
+        | Compression::Dwaa => 32,
+        Compression::Dwab => 256,
+        _ => 1,
+    }
+}
+
+/// Can a single scanline of an image stored with `compression` be decoded
+/// without decoding any of its neighbours?
+///
+/// This is true for [`Compression::No`], [`Compression::Rle`] and
+/// [`Compression::Zips`], which compress each scanline on its own. Every
+/// other method compresses blocks of
+/// [`num_scanlines_per_block()`] scanlines together, so reading one
+/// scanline means decoding its whole block, and a reader picking out a small
+/// region may do better to read whole blocks at once.
+///
+pub fn supports_random_access(compression: Compression) -> bool {
+    num_scanlines_per_block(compression) == 1
+}
+
+/// Get a rough compressed-to-uncompressed size ratio for `compression`, e.g.
+/// 0.5 means the pixel data is expected to take half as much space.
+///
+/// These are heuristics based on typical results for rendered and
+/// photographic half-float images, and are only meant for things like size
+/// hints in a user interface. The real ratio depends heavily on the image:
+/// noisy images compress much worse, and flat colors much better.
+///
+/// [`Compression::B44`] and [`Compression::B44a`] always compress half
+/// channels by a fixed 32:14, but leave float and uint channels
+/// uncompressed.
+///
+pub fn estimate_ratio(compression: Compression) -> f32 {
+    match compression {
+        Compression::No => 1.0,
+        Compression::Rle => 0.75,
+        Compression::Zips => 0.55,
+        Compression::Zip => 0.5,
+        Compression::Piz => 0.45,
+        Compression::Pxr24 => 0.4,
+        Compression::B44 => 0.44,
+        Compression::B44a => 0.35,
+        Compression::Dwaa => 0.1,
+        Compression::Dwab => 0.08,
+        _ => 1.0,
+    }
+}
+
+/// Get a rough estimate of how many bytes the pixel data of an image with
+/// `header` will take when stored with `compression`.
+///
+/// This is the uncompressed size of all the channels of the data window,
+/// allowing for subsampling, scaled by [`estimate_ratio()`]. It does not
+/// include the header or the offset tables, and like
+/// [`estimate_ratio()`] it is only approximate.
+///
+/// ```no_run
+/// use openexr::core::{compression::estimate_bytes, header::Header, Compression};
+///
+/// let header = Header::from_dimensions(1920, 1080);
+/// let kb = estimate_bytes(Compression::Dwaa, &header) / 1024;
+/// ```
+///
+pub fn estimate_bytes(compression: Compression, header: &Header) -> usize {
+    let dw = *header.data_window::<[i32; 4]>();
+    let uncompressed: usize = header
+        .channels()
+        .iter()
+        .map(|(_, c)| {
+            let (nx, ny) =
+                sample_counts(dw, [c.x_sampling.max(1), c.y_sampling.max(1)]);
+            let bytes = match PixelType::from(c.type_) {
+                PixelType::Half => 2,
+                _ => 4,
+            };
+            nx * ny * bytes
+        })
+        .sum();
+
+    (uncompressed as f64 * estimate_ratio(compression) as f64).round() as usize
+}
+
+#[cfg(test)]
+#[test]
+fn test_recommended_for() {
+    assert_eq!(
+        recommended_for(ContentHint::PhotographicColor),
+        Compression::Dwaa
+    );
+    assert_eq!(
+        recommended_for(ContentHint::RenderedColor),
+        Compression::Piz
+    );
+    assert_eq!(
+        recommended_for(ContentHint::DataChannels),
+        Compression::Zips
+    );
+    assert_eq!(recommended_for(ContentHint::DeepData), Compression::Zips);
+}
+
+#[cfg(test)]
+#[test]
+fn test_all() {
+    let all = all();
+    assert_eq!(all.len(), 10);
+    assert_eq!(all[0], Compression::No);
+    for (i, a) in all.iter().enumerate() {
+        assert!(!all[i + 1..].contains(a));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_num_scanlines_per_block() {
+    let expected = [
+        (Compression::No, 1),
+        (Compression::Rle, 1),
+        (Compression::Zips, 1),
+        (Compression::Zip, 16),
+        (Compression::Piz, 32),
+        (Compression::Pxr24, 16),
+        (Compression::B44, 32),
+        (Compression::B44a, 32),
+        (Compression::Dwaa, 32),
+        (Compression::Dwab, 256),
+    ];
+    assert_eq!(expected.len(), all().len());
+    for (compression, lines) in &expected {
+        assert_eq!(
+            num_scanlines_per_block(*compression),
+            *lines,
+            "{:?}",
+            compression
+        );
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_supports_random_access() {
+    for compression in &[Compression::No, Compression::Rle, Compression::Zips] {
+        assert!(supports_random_access(*compression), "{:?}", compression);
+    }
+    for compression in &[
+        Compression::Zip,
+        Compression::Piz,
+        Compression::Pxr24,
+        Compression::B44,
+        Compression::B44a,
+        Compression::Dwaa,
+        Compression::Dwab,
+    ] {
+        assert!(!supports_random_access(*compression), "{:?}", compression);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_estimate_bytes() {
+    use crate::core::channel_list::{CHANNEL_FLOAT, CHANNEL_HALF};
+
+    for c in all() {
+        let ratio = estimate_ratio(*c);
+        assert!(ratio > 0.0 && ratio <= 1.0, "{:?} has ratio {}", c, ratio);
+    }
+    assert!(
+        estimate_ratio(Compression::Dwaa) < estimate_ratio(Compression::Zip)
+    );
+
+    let mut header = Header::from_dimensions(100, 50);
+    header.channels_mut().insert("R", &CHANNEL_HALF);
+    header.channels_mut().insert("Z", &CHANNEL_FLOAT);
+    assert_eq!(estimate_bytes(Compression::No, &header), 100 * 50 * 6);
+    assert_eq!(estimate_bytes(Compression::Zip, &header), 100 * 50 * 3);
+}
@@ -1,5 +1,7 @@
 use openexr_sys as sys;
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
 
 use crate::core::refptr::{OpaquePtr, Ref, RefMut};
 
@@ -31,6 +33,49 @@ impl CppString {
         }
     }
 
+    /// Replace the contents of this string with `string`, reusing the
+    /// existing allocation where possible.
+    ///
+    /// # Panics
+    /// If `string` contains a NUL byte
+    ///
+    pub fn assign(&mut self, string: &str) {
+        assert!(!string.contains('\0'), "Inner NUL bytes in string");
+        unsafe {
+            let mut dummy = std::ptr::null_mut();
+            sys::std_string_assign(
+                self.0,
+                &mut dummy,
+                string.as_ptr() as *const c_char,
+                string.len() as u64,
+            );
+        }
+    }
+
+    /// Call `f` with a `CppString` holding `string`.
+    ///
+    /// The underlying std::string is cached per thread, so calling this in a
+    /// loop does not construct and destroy a new one every time.
+    ///
+    pub(crate) fn with_scratch<F, R>(string: &str, f: F) -> R
+    where
+        F: FnOnce(&CppString) -> R,
+    {
+        thread_local! {
+            static SCRATCH: RefCell<Option<CppString>> = RefCell::new(None);
+        }
+
+        SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+            Ok(mut scratch) => {
+                let s = scratch.get_or_insert_with(|| CppString::new(""));
+                s.assign(string);
+                f(s)
+            }
+            // re-entered from inside `f`, so fall back to a temporary
+            Err(_) => f(&CppString::new(string)),
+        })
+    }
+
     pub fn as_str(self: &CppString) -> &str {
         let mut cptr = std::ptr::null();
         unsafe {
@@ -180,3 +225,21 @@ impl Default for CppVectorString {
         CppVectorString::new()
     }
 }
+
+#[cfg(test)]
+#[test]
+fn cpp_string_assign1() {
+    let mut s = CppString::new("a fairly long string to force an allocation");
+    s.assign("short");
+    assert_eq!(s.as_str(), "short");
+
+    let len = CppString::with_scratch("outer", |outer| {
+        // nested use must not clobber the outer string
+        CppString::with_scratch("inner", |inner| {
+            assert_eq!(inner.as_str(), "inner");
+        });
+        assert_eq!(outer.as_str(), "outer");
+        outer.as_str().len()
+    });
+    assert_eq!(len, 5);
+}
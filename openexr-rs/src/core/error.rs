@@ -20,6 +20,8 @@ pub enum Error {
     Io(String),
     #[error("No preview image: {0}")]
     NoPreviewImage(String),
+    #[error("Invalid header: {}", .0.join("; "))]
+    InvalidHeader(Vec<String>),
 }
 
 impl From<sys::Error> for Error {
@@ -37,3 +39,71 @@ impl From<sys::Error> for Error {
         }
     }
 }
+
+impl From<std::ffi::NulError> for Error {
+    fn from(e: std::ffi::NulError) -> Error {
+        Error::InvalidArgument(format!(
+            "string contains a NUL byte at position {}",
+            e.nul_position()
+        ))
+    }
+}
+
+impl Error {
+    /// Might the operation that returned this error succeed if it is retried?
+    ///
+    /// Only [`Error::Io`] is considered transient, as it covers failures of
+    /// the underlying stream such as timeouts on network file systems. All
+    /// other errors, e.g. [`Error::Input`] for corrupt or truncated files or
+    /// [`Error::InvalidArgument`] for bad arguments, will fail again in the
+    /// same way.
+    ///
+    /// OpenEXR reports all stream failures as [`Error::Io`], including
+    /// permanent ones such as a missing file, so retries should still be
+    /// limited.
+    ///
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Error::Io(_))
+    }
+
+    /// Prefix the message of this error with `context`, keeping the same
+    /// variant
+    ///
+    pub(crate) fn with_context(self, context: &str) -> Error {
+        match self {
+            Error::OutOfRange => Error::OutOfRange,
+            Error::Base(s) => Error::Base(format!("{}: {}", context, s)),
+            Error::Input(s) => Error::Input(format!("{}: {}", context, s)),
+            Error::LogicError(s) => {
+                Error::LogicError(format!("{}: {}", context, s))
+            }
+            Error::OutOfMemory(s) => {
+                Error::OutOfMemory(format!("{}: {}", context, s))
+            }
+            Error::InvalidType(s) => {
+                Error::InvalidType(format!("{}: {}", context, s))
+            }
+            Error::InvalidArgument(s) => {
+                Error::InvalidArgument(format!("{}: {}", context, s))
+            }
+            Error::Io(s) => Error::Io(format!("{}: {}", context, s)),
+            Error::NoPreviewImage(s) => {
+                Error::NoPreviewImage(format!("{}: {}", context, s))
+            }
+            Error::InvalidHeader(v) => Error::InvalidHeader(
+                v.into_iter()
+                    .map(|s| format!("{}: {}", context, s))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn error_is_transient1() {
+    assert!(Error::Io("timed out".to_string()).is_transient());
+    assert!(!Error::Input("bad magic number".to_string()).is_transient());
+    assert!(!Error::OutOfRange.is_transient());
+    assert!(!Error::InvalidHeader(vec![]).is_transient());
+}
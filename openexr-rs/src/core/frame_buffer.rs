@@ -1,5 +1,6 @@
 use openexr_sys as sys;
 
+use crate::core::channel_list::pixel_type_name;
 pub use crate::core::{
     error::Error,
     refptr::{OpaquePtr, Ref, RefMut},
@@ -16,6 +17,9 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 pub struct FrameBuffer {
     pub(crate) ptr: *mut sys::Imf_FrameBuffer_t,
     pub(crate) frames: Option<Vec<Frame>>,
+    // the pixel window covered by the storage behind each slice, for the
+    // slices where we know it
+    pub(crate) windows: Vec<(String, [i32; 4])>,
 }
 
 unsafe impl OpaquePtr for FrameBuffer {
@@ -35,6 +39,7 @@ impl FrameBuffer {
         FrameBuffer {
             ptr,
             frames: Some(Vec::new()),
+            windows: Vec::new(),
         }
     }
 
@@ -51,6 +56,9 @@ impl FrameBuffer {
             sys::Imf_FrameBuffer_insert(self.ptr, c_name.as_ptr(), &slice.0)
                 .into_result()?;
         }
+        // the slice has been replaced, so whatever we knew about its storage
+        // no longer applies
+        self.windows.retain(|(n, _)| n != name);
 
         Ok(())
     }
@@ -131,6 +139,162 @@ impl FrameBuffer {
         }
     }
 
+    /// Insert a [`Slice`] called `name` that reads into or writes from
+    /// `data`, a single channel of pixels covering `data_window`.
+    ///
+    /// When reading, if the file does not contain a channel called `name`,
+    /// every pixel of `data` is set to `fill` instead. This makes it safe to
+    /// ask for a superset of the channels in a file, e.g. requesting `"A"` with
+    /// a fill of 1.0 gives an opaque alpha for an RGB file.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `T` has more than one channel, if
+    /// `data` is not exactly the size of `data_window`, or if `name` is the
+    /// empty string
+    ///
+    /// # Safety
+    /// The `FrameBuffer` only stores a pointer to `data`, so `data` must
+    /// outlive every read or write that uses this `FrameBuffer`, and must not
+    /// be accessed through any other reference while one is in progress.
+    ///
+    pub unsafe fn insert_channel_with_fill<T, B>(
+        &mut self,
+        name: &str,
+        data: &mut [T],
+        data_window: B,
+        fill: f64,
+    ) -> Result<()>
+    where
+        T: Pixel,
+        B: Bound2<i32>,
+    {
+        if T::NUM_CHANNELS != 1 {
+            return Err(Error::InvalidArgument(format!(
+                "pixel type for channel \"{}\" has {} channels, expected 1",
+                name,
+                T::NUM_CHANNELS
+            )));
+        }
+
+        let dw = data_window.as_slice();
+        check_channel_len(name, data.len(), dw)?;
+        let w = dw[2] as i64 - dw[0] as i64 + 1;
+
+        self.insert(
+            name,
+            &Slice::with_data_window(
+                T::CHANNEL_TYPE,
+                data.as_mut_ptr() as *const u8,
+                *dw,
+            )
+            .x_stride(T::STRIDE)
+            .y_stride(T::STRIDE * w as usize)
+            .fill_value(fill)
+            .build()?,
+        )?;
+        self.windows.push((name.to_string(), *dw));
+
+        Ok(())
+    }
+
+    /// Insert a [`PixelType::Half`] channel called `name` covering
+    /// `data_window` whose pixels are converted from the `f32` pixels in
+    /// `data`.
+    ///
+    /// The converted pixels are stored in a staging [`Frame`] owned by this
+    /// `FrameBuffer`, so unlike [`FrameBuffer::insert`] there are no lifetime
+    /// requirements on `data`.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `data` is not exactly the size of
+    /// `data_window`, or if `name` is the empty string
+    ///
+    pub fn insert_channel_f32_as_half<B: Bound2<i32>>(
+        &mut self,
+        name: &str,
+        data: &[f32],
+        data_window: B,
+    ) -> Result<FrameHandle> {
+        let dw = *data_window.as_slice();
+        check_channel_len(name, data.len(), &dw)?;
+
+        let staging = data.iter().map(|v| half::f16::from_f32(*v)).collect();
+        self.insert_frame(Frame::with_vec::<half::f16, _, _>(
+            &[name],
+            staging,
+            dw,
+        )?)
+    }
+
+    /// Insert a [`PixelType::Half`] channel called `name` covering
+    /// `data_window` to be read into a staging [`Frame`] owned by this
+    /// `FrameBuffer`.
+    ///
+    /// After reading, get the pixels converted to `f32` with
+    /// [`FrameBuffer::half_channel_as_f32`] using the returned handle.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `data_window` is empty, or if `name`
+    /// is the empty string
+    ///
+    pub fn insert_channel_half_as_f32<B: Bound2<i32>>(
+        &mut self,
+        name: &str,
+        data_window: B,
+    ) -> Result<FrameHandle> {
+        let dw = *data_window.as_slice();
+        if dw[2] < dw[0] || dw[3] < dw[1] {
+            return Err(Error::InvalidArgument(format!(
+                "data window for channel \"{}\" is empty: {:?}",
+                name, dw
+            )));
+        }
+
+        self.insert_frame(Frame::with_vec::<half::f16, _, _>(
+            &[name],
+            Vec::new(),
+            dw,
+        )?)
+    }
+
+    /// Get the pixels of the staging [`Frame`] inserted with
+    /// [`FrameBuffer::insert_channel_half_as_f32`] or
+    /// [`FrameBuffer::insert_channel_f32_as_half`], converted to `f32`.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `handle` does not refer to a frame
+    /// in this `FrameBuffer`
+    /// * [`Error::InvalidType`] - if the frame is not a single
+    /// [`PixelType::Half`] channel
+    ///
+    pub fn half_channel_as_f32(&self, handle: FrameHandle) -> Result<Vec<f32>> {
+        let frame = self
+            .frames
+            .as_ref()
+            .and_then(|frames| frames.get(handle.0))
+            .ok_or_else(|| {
+                Error::InvalidArgument(format!(
+                    "No frame with handle {} in this frame buffer",
+                    handle.0
+                ))
+            })?;
+
+        if frame.channel_type != PixelType::Half
+            || frame.channel_names.len() != 1
+        {
+            return Err(Error::InvalidType(format!(
+                "Frame {:?} is not a single half channel",
+                frame.channel_names
+            )));
+        }
+
+        Ok(frame
+            .as_slice::<half::f16>()
+            .iter()
+            .map(|v| v.to_f32())
+            .collect())
+    }
+
     pub fn insert_frame(&mut self, frame: Frame) -> Result<FrameHandle> {
         let mut ptr = frame.ptr;
         let w = frame.data_window[2] - frame.data_window[0] + 1;
@@ -147,6 +311,7 @@ impl FrameBuffer {
                 .y_stride(ystride)
                 .build()?,
             )?;
+            self.windows.push((chan.clone(), frame.data_window));
 
             ptr = unsafe { ptr.add(frame.channel_stride) };
         }
@@ -164,6 +329,73 @@ impl FrameBuffer {
     }
 }
 
+impl FrameBuffer {
+    /// Check that the storage behind every slice whose extent is known, i.e.
+    /// those inserted with [`FrameBuffer::insert_channel_with_fill`] or as
+    /// part of a [`Frame`], covers all of `data_window`, so that reading
+    /// `data_window` cannot write outside of it.
+    ///
+    pub(crate) fn check_covers(&self, data_window: &[i32; 4]) -> Result<()> {
+        for (name, w) in &self.windows {
+            if w[0] > data_window[0]
+                || w[1] > data_window[1]
+                || w[2] < data_window[2]
+                || w[3] < data_window[3]
+            {
+                return Err(Error::InvalidArgument(format!(
+                    "data for channel \"{}\" covers {:?} but the data \
+                    window of the file is {:?}",
+                    name, w, data_window
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that a single channel buffer of `len` pixels exactly covers
+/// `data_window`
+///
+fn check_channel_len(
+    name: &str,
+    len: usize,
+    data_window: &[i32; 4],
+) -> Result<()> {
+    let w = data_window[2] as i64 - data_window[0] as i64 + 1;
+    let h = data_window[3] as i64 - data_window[1] as i64 + 1;
+    if w < 1 || h < 1 || len as i64 != w * h {
+        Err(Error::InvalidArgument(format!(
+            "data for channel \"{}\" has {} pixels but the data window \
+            is {}x{}",
+            name, len, w, h
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that the pixels of `name`, a channel of `pixel_type` or `None` if it
+/// is not in the file, can be read into a buffer of `T` without losing data
+///
+pub(crate) fn check_channel_type<T: Pixel>(
+    name: &str,
+    pixel_type: Option<PixelType>,
+) -> Result<()> {
+    match pixel_type {
+        Some(pixel_type) if !compatible_with::<T>(pixel_type) => {
+            Err(Error::InvalidArgument(format!(
+                "channel \"{}\" is {} and would lose data if read into a {} \
+                buffer",
+                name,
+                pixel_type_name(pixel_type),
+                pixel_type_name(T::CHANNEL_TYPE)
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
 impl Drop for FrameBuffer {
     fn drop(&mut self) {
         unsafe {
@@ -445,6 +677,30 @@ pub trait Pixel: Zero + Clone {
     const CHANNEL_STRIDE: usize = std::mem::size_of::<Self>();
 }
 
+/// Can a channel of `pixel_type` be read into a buffer of `T` without losing
+/// data?
+///
+/// OpenEXR converts between pixel types when reading, so any buffer type can
+/// be used, but only some conversions are exact: every type can be read into
+/// a buffer of the same type, and [`PixelType::Half`] can also be read into
+/// an `f32` buffer. Reading [`PixelType::Float`] or [`PixelType::Uint`] into
+/// `f16`, [`PixelType::Uint`] into `f32`, or either float type into `u32`
+/// rounds, clamps or truncates the values, so those are rejected.
+///
+/// ```no_run
+/// use openexr::core::{frame_buffer::compatible_with, PixelType};
+///
+/// assert!(compatible_with::<half::f16>(PixelType::Half));
+/// assert!(compatible_with::<f32>(PixelType::Half));
+/// assert!(!compatible_with::<half::f16>(PixelType::Float));
+/// ```
+///
+pub fn compatible_with<T: Pixel>(pixel_type: PixelType) -> bool {
+    T::CHANNEL_TYPE == pixel_type
+        || (T::CHANNEL_TYPE == PixelType::Float
+            && pixel_type == PixelType::Half)
+}
+
 impl Pixel for half::f16 {
     // type Type = Self;
     const CHANNEL_TYPE: PixelType = PixelType::Half;
@@ -715,3 +971,138 @@ impl Drop for Frame {
         }
     }
 }
+
+#[cfg(test)]
+#[test]
+fn insert_channel_with_fill1() -> Result<()> {
+    use crate::{
+        core::{header::Header, input_file::InputFile},
+        rgba::{rgba::RgbaChannels, rgba_file::RgbaOutputFile},
+        tests::load_ferris,
+    };
+    use half::f16;
+
+    let (pixels, width, height) = load_ferris();
+
+    // write a file with no alpha channel
+    let header = Header::from_dimensions(width, height);
+    let mut file = RgbaOutputFile::new(
+        "insert_channel_with_fill1.exr",
+        &header,
+        RgbaChannels::WriteRgb,
+        1,
+    )?;
+    file.set_frame_buffer(&pixels, 1, width as usize)?;
+    file.write_pixels(height)?;
+    std::mem::drop(file);
+
+    let mut file = InputFile::new("insert_channel_with_fill1.exr", 1)?;
+    let data_window = *file.header().data_window::<[i32; 4]>();
+
+    let mut r = vec![f16::ZERO; (width * height) as usize];
+    let mut a = vec![f16::ZERO; (width * height) as usize];
+    let mut frame_buffer = FrameBuffer::new();
+    unsafe {
+        frame_buffer.insert_channel_with_fill("R", &mut r, data_window, 0.5)
+    }?;
+    unsafe {
+        frame_buffer.insert_channel_with_fill("A", &mut a, data_window, 1.0)
+    }?;
+
+    // wrong size buffers are rejected
+    assert!(unsafe {
+        frame_buffer.insert_channel_with_fill(
+            "G",
+            &mut r[1..],
+            data_window,
+            0.0,
+        )
+    }
+    .is_err());
+
+    file.set_frame_buffer(&frame_buffer)?;
+    file.read_pixels(data_window[1], data_window[3])?;
+
+    assert!(a.iter().all(|v| *v == f16::ONE));
+    assert!(r.iter().zip(pixels.iter()).all(|(r, p)| *r == p.r));
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn insert_channel_f32_as_half1() -> Result<()> {
+    use crate::core::{
+        channel_list::Channel, header::Header, input_file::InputFile,
+        output_file::OutputFile,
+    };
+
+    let (width, height) = (8, 4);
+    let data_window = [0, 0, width - 1, height - 1];
+    let data = (0..width * height)
+        .map(|i| i as f32 * 0.25)
+        .collect::<Vec<_>>();
+
+    let mut header = Header::from_dimensions(width, height);
+    header.channels_mut().insert(
+        "Y",
+        &Channel {
+            type_: PixelType::Half.into(),
+            x_sampling: 1,
+            y_sampling: 1,
+            p_linear: true,
+        },
+    );
+
+    {
+        let mut frame_buffer = FrameBuffer::new();
+        frame_buffer.insert_channel_f32_as_half("Y", &data, data_window)?;
+        assert!(frame_buffer
+            .insert_channel_f32_as_half("Z", &data[1..], data_window)
+            .is_err());
+
+        let mut file =
+            OutputFile::new("insert_channel_f32_as_half1.exr", &header, 1)?;
+        file.set_frame_buffer(&frame_buffer)?;
+        unsafe {
+            file.write_pixels(height)?;
+        }
+    }
+
+    let mut file = InputFile::new("insert_channel_f32_as_half1.exr", 1)?;
+    let mut frame_buffer = FrameBuffer::new();
+    let handle = frame_buffer.insert_channel_half_as_f32("Y", data_window)?;
+    file.set_frame_buffer(&frame_buffer)?;
+    file.read_pixels(0, height - 1)?;
+
+    // all the test values are exactly representable as half
+    assert_eq!(frame_buffer.half_channel_as_f32(handle)?, data);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn compatible_with1() {
+    use half::f16;
+
+    assert!(compatible_with::<f16>(PixelType::Half));
+    assert!(compatible_with::<f32>(PixelType::Float));
+    assert!(compatible_with::<u32>(PixelType::Uint));
+    assert!(compatible_with::<f32>(PixelType::Half));
+    assert!(!compatible_with::<f16>(PixelType::Float));
+    assert!(!compatible_with::<f16>(PixelType::Uint));
+    assert!(!compatible_with::<f32>(PixelType::Uint));
+    assert!(!compatible_with::<u32>(PixelType::Half));
+    assert!(!compatible_with::<u32>(PixelType::Float));
+
+    assert!(check_channel_type::<f32>("Z", Some(PixelType::Float)).is_ok());
+    assert!(check_channel_type::<f32>("R", Some(PixelType::Half)).is_ok());
+    assert!(check_channel_type::<f32>("missing", None).is_ok());
+    match check_channel_type::<f16>("Z", Some(PixelType::Float)) {
+        Err(Error::InvalidArgument(msg)) => {
+            assert!(msg.contains("\"Z\"") && msg.contains("float"))
+        }
+        r => panic!("expected InvalidArgument, got {:?}", r),
+    }
+}
@@ -0,0 +1,148 @@
+use imath_traits::Bound2;
+
+/// Extra operations on integer boxes, such as the data and display windows
+/// returned from [`Header`](crate::core::header::Header).
+///
+/// This is implemented for every type implementing [`Bound2<i32>`] so it can
+/// be used directly on whichever box type you have chosen, e.g.:
+///
+/// ```no_run
+/// use openexr::core::imath::Box2iOps;
+/// use openexr::core::header::Header;
+///
+/// let a = Header::from_windows([0, 0, 99, 99], [0, 0, 99, 99]);
+/// let b = Header::from_windows([50, 50, 149, 149], [0, 0, 99, 99]);
+///
+/// let common: Option<[i32; 4]> =
+///     a.data_window::<[i32; 4]>().intersect(b.data_window());
+/// assert_eq!(common, Some([50, 50, 99, 99]));
+/// ```
+///
+/// As with Imath, a box is empty if its min is greater than its max on either
+/// axis.
+///
+pub trait Box2iOps: Sized {
+    /// Is this box empty?
+    ///
+    fn is_empty_box(&self) -> bool;
+
+    /// Get the region covered by both `self` and `other`.
+    ///
+    /// # Returns
+    /// * `Some(box)` - the intersection of the two boxes
+    /// * `None` - if the boxes are disjoint or either box is empty
+    ///
+    fn intersect(&self, other: &Self) -> Option<Self>;
+
+    /// Get the smallest box containing both `self` and `other`.
+    ///
+    /// Empty boxes are ignored, so the union of an empty box with `other` is
+    /// `other`.
+    ///
+    fn union(&self, other: &Self) -> Self;
+}
+
+impl<B> Box2iOps for B
+where
+    B: Bound2<i32>,
+{
+    fn is_empty_box(&self) -> bool {
+        let a = self.as_slice();
+        a[0] > a[2] || a[1] > a[3]
+    }
+
+    fn intersect(&self, other: &Self) -> Option<Self> {
+        if self.is_empty_box() || other.is_empty_box() {
+            return None;
+        }
+
+        let a = self.as_slice();
+        let b = other.as_slice();
+        let result = [
+            a[0].max(b[0]),
+            a[1].max(b[1]),
+            a[2].min(b[2]),
+            a[3].min(b[3]),
+        ];
+
+        if result[0] > result[2] || result[1] > result[3] {
+            None
+        } else {
+            Some(B::from_slice(&result))
+        }
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        let a = self.as_slice();
+        let b = other.as_slice();
+
+        if self.is_empty_box() {
+            return B::from_slice(&[b[0], b[1], b[2], b[3]]);
+        } else if other.is_empty_box() {
+            return B::from_slice(&[a[0], a[1], a[2], a[3]]);
+        }
+
+        B::from_slice(&[
+            a[0].min(b[0]),
+            a[1].min(b[1]),
+            a[2].max(b[2]),
+            a[3].max(b[3]),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Box2iOps;
+
+    #[test]
+    fn test_intersect_overlapping() {
+        let a = [0, 0, 99, 99];
+        let b = [50, 20, 149, 79];
+
+        assert_eq!(a.intersect(&b), Some([50, 20, 99, 79]));
+        assert_eq!(b.intersect(&a), Some([50, 20, 99, 79]));
+    }
+
+    #[test]
+    fn test_intersect_touching() {
+        let a = [0, 0, 9, 9];
+        let b = [9, 9, 19, 19];
+
+        assert_eq!(a.intersect(&b), Some([9, 9, 9, 9]));
+    }
+
+    #[test]
+    fn test_intersect_disjoint() {
+        let a = [0, 0, 9, 9];
+        let b = [10, 0, 19, 9];
+
+        assert_eq!(a.intersect(&b), None);
+    }
+
+    #[test]
+    fn test_intersect_empty() {
+        let a = [0, 0, 9, 9];
+        let empty = [5, 5, 4, 4];
+
+        assert_eq!(a.intersect(&empty), None);
+        assert_eq!(empty.intersect(&a), None);
+    }
+
+    #[test]
+    fn test_union() {
+        let a = [0, 0, 9, 9];
+        let b = [20, -5, 29, 4];
+
+        assert_eq!(a.union(&b), [0, -5, 29, 9]);
+    }
+
+    #[test]
+    fn test_union_empty() {
+        let a = [0, 0, 9, 9];
+        let empty = [5, 5, 4, 4];
+
+        assert_eq!(a.union(&empty), a);
+        assert_eq!(empty.union(&a), a);
+    }
+}
@@ -1,13 +1,19 @@
 use openexr_sys as sys;
 
-use std::ffi::CString;
-use std::path::Path;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
 
 use crate::core::{
     error::Error,
-    frame_buffer::{Frame, FrameBuffer, FrameBufferRef},
+    frame_buffer::{
+        check_channel_type, Frame, FrameBuffer, FrameBufferRef, Pixel, Slice,
+    },
     header::HeaderRef,
+    Compression, LineOrder, PixelType,
 };
+use crate::tiled::tiled_input_file::TiledInputFile;
+use crate::util::{cstr_to_path, path_to_cstring};
 
 type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -17,20 +23,22 @@ pub struct InputFile(pub(crate) *mut sys::Imf_InputFile_t);
 impl InputFile {
     /// Open the file at path `filename` and read the header.
     ///
+    /// No pixels are read until [`read_pixels()`](InputFile::read_pixels) is
+    /// called, so this is cheap enough to use just to inspect the file, e.g.
+    /// with [`info()`](InputFile::info).
+    ///
+    /// On Unix the path is passed to OpenEXR as raw bytes, so it does not
+    /// need to be valid UTF-8.
+    ///
     /// # Errors
     /// * [`Error::Base`] - if the file cannot be opened
+    /// * [`Error::InvalidArgument`] - if `filename` contains a NUL byte
     ///
     pub fn new<P: AsRef<Path>>(
         filename: P,
         num_threads: i32,
     ) -> Result<InputFile> {
-        let c_filename = CString::new(
-            filename
-                .as_ref()
-                .to_str()
-                .expect("Invalid bytes in filename"),
-        )
-        .expect("Internal null bytes in filename");
+        let c_filename = path_to_cstring(filename.as_ref())?;
 
         let mut ptr = std::ptr::null_mut();
         unsafe {
@@ -43,7 +51,20 @@ impl InputFile {
 
     /// Access to the file [`Header`](crate::core::header::Header)
     ///
-    pub fn header(&self) -> HeaderRef {
+    /// The header is owned by the file, so the returned [`HeaderRef`] borrows
+    /// from `self` and cannot outlive it:
+    ///
+    /// ```compile_fail
+    /// use openexr::core::input_file::InputFile;
+    ///
+    /// let header = {
+    ///     let file = InputFile::new("image.exr", 4).unwrap();
+    ///     file.header()
+    /// };
+    /// println!("{:?}", header.compression());
+    /// ```
+    ///
+    pub fn header(&self) -> HeaderRef<'_> {
         unsafe {
             let mut ptr = std::ptr::null();
             sys::Imf_InputFile_header(self.0, &mut ptr);
@@ -55,6 +76,38 @@ impl InputFile {
         }
     }
 
+    /// Get a summary of the image layout from the header, without reading
+    /// any pixels
+    ///
+    /// ```no_run
+    /// use openexr::core::input_file::InputFile;
+    ///
+    /// let info = InputFile::new("image.exr", 1)?.info();
+    /// println!("{}x{} {:?}", info.width, info.height, info.compression);
+    /// for (name, pixel_type) in &info.channels {
+    ///     println!("{}: {:?}", name, pixel_type);
+    /// }
+    /// # Ok::<(), openexr::core::error::Error>(())
+    /// ```
+    ///
+    pub fn info(&self) -> FileInfo {
+        let header = self.header();
+        let dw = header.data_window::<[i32; 4]>();
+        FileInfo {
+            width: dw[2] - dw[0] + 1,
+            height: dw[3] - dw[1] + 1,
+            channels: header
+                .channels()
+                .iter()
+                .map(|(name, channel)| {
+                    (name.to_string(), PixelType::from(channel.type_))
+                })
+                .collect(),
+            compression: header.compression(),
+            line_order: header.line_order(),
+        }
+    }
+
     /// Access to the file format version
     ///
     pub fn version(&self) -> i32 {
@@ -74,13 +127,37 @@ impl InputFile {
     /// The current frame buffer can be changed after each call
     /// to `read_pixels()`.
     ///
+    /// Channels inserted with
+    /// [`FrameBuffer::insert_channel_with_fill()`](crate::core::frame_buffer::FrameBuffer::insert_channel_with_fill)
+    /// or as part of a [`Frame`] are checked against the data window of the
+    /// file, so that a buffer too small for the image is rejected here rather
+    /// than written out of bounds by
+    /// [`read_pixels()`](InputFile::read_pixels). Slices inserted directly
+    /// with
+    /// [`FrameBuffer::insert()`](crate::core::frame_buffer::FrameBuffer::insert)
+    /// are built from raw pointers, so their extent is unknown and it is up
+    /// to the caller to get it right.
+    ///
     /// # Errors
-    /// * [`Error::InvalidArgument`] - if the sampling factors do not match or
-    /// if the frame buffer does not have a sample count slice.
+    /// * [`Error::InvalidArgument`] - if the sampling factors do not match,
+    /// if the frame buffer does not have a sample count slice, or if the
+    /// storage of a checked channel does not cover the data window
     ///
     pub fn set_frame_buffer(
         &mut self,
         frame_buffer: &FrameBuffer,
+    ) -> Result<()> {
+        frame_buffer.check_covers(self.header().data_window())?;
+        self.set_frame_buffer_unchecked(frame_buffer)
+    }
+
+    /// Set the frame buffer without checking the extent of its channels,
+    /// for internal callers that only read the part of the data window that
+    /// their storage covers.
+    ///
+    fn set_frame_buffer_unchecked(
+        &mut self,
+        frame_buffer: &FrameBuffer,
     ) -> Result<()> {
         unsafe {
             sys::Imf_InputFile_setFrameBuffer(self.0, frame_buffer.ptr)
@@ -103,6 +180,10 @@ impl InputFile {
 
     /// Check if all pixels in the data window are present in the input file
     ///
+    /// A truncated file (for example one that was only partially written)
+    /// can still be opened and will return `false` here rather than producing
+    /// an error.
+    ///
     pub fn is_complete(&self) -> bool {
         let mut v = false;
         unsafe {
@@ -156,6 +237,255 @@ impl InputFile {
         Ok(())
     }
 
+    /// Read the pixels of `channel` inside `region`, given as
+    /// `[min_x, min_y, max_x, max_y]` inclusive, and return them one row after
+    /// another.
+    ///
+    /// For scanline files only the scanlines that intersect `region` are
+    /// read, then trimmed to its horizontal span. For tiled files the file is
+    /// reopened as a [`TiledInputFile`] and only the tiles of the highest
+    /// resolution level that overlap `region` are read.
+    ///
+    /// If the file does not contain `channel`, the result is filled with
+    /// zeroes. Otherwise the pixels are converted to `T`, as long as that
+    /// does not lose data, see
+    /// [`compatible_with()`](crate::core::frame_buffer::compatible_with).
+    ///
+    /// This replaces the current frame buffer of the `InputFile` with an
+    /// empty one, so [`set_frame_buffer()`](InputFile::set_frame_buffer) must
+    /// be called again before any further calls to
+    /// [`read_pixels()`](InputFile::read_pixels).
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `T` has more than one channel or
+    /// cannot hold the pixels of `channel` without losing data, or if
+    /// `region` is empty or
+    /// not inside the data window
+    /// * [`Error::Base`] - if any other error occurs
+    ///
+    pub fn read_region<T: Pixel>(
+        &mut self,
+        region: [i32; 4],
+        channel: &str,
+    ) -> Result<Vec<T>> {
+        let (dw, tiled) = {
+            let header = self.header();
+            check_channel_type::<T>(
+                channel,
+                header.channel_pixel_type(channel),
+            )?;
+            (
+                *header.data_window::<[i32; 4]>(),
+                header.has_tile_description(),
+            )
+        };
+
+        if region[2] < region[0]
+            || region[3] < region[1]
+            || region[0] < dw[0]
+            || region[1] < dw[1]
+            || region[2] > dw[2]
+            || region[3] > dw[3]
+        {
+            return Err(Error::InvalidArgument(format!(
+                "region {:?} is not inside the data window {:?}",
+                region, dw
+            )));
+        }
+
+        // The window of pixels that OpenEXR will actually write: whole tiles
+        // for tiled files, whole rows for scanline files.
+        let mut tiled_file = None;
+        let (window, tile_range) = if tiled {
+            let file = TiledInputFile::new(self.file_name(), 1)?;
+            let tx = file.tile_x_size() as i32;
+            let ty = file.tile_y_size() as i32;
+            tiled_file = Some(file);
+
+            let dx1 = (region[0] - dw[0]) / tx;
+            let dy1 = (region[1] - dw[1]) / ty;
+            let dx2 = (region[2] - dw[0]) / tx;
+            let dy2 = (region[3] - dw[1]) / ty;
+            (
+                [
+                    dw[0] + dx1 * tx,
+                    dw[1] + dy1 * ty,
+                    (dw[0] + (dx2 + 1) * tx - 1).min(dw[2]),
+                    (dw[1] + (dy2 + 1) * ty - 1).min(dw[3]),
+                ],
+                (dx1, dx2, dy1, dy2),
+            )
+        } else {
+            ([dw[0], region[1], dw[2], region[3]], (0, 0, 0, 0))
+        };
+
+        let window_w = (window[2] - window[0] + 1) as usize;
+        let window_h = (window[3] - window[1] + 1) as usize;
+        let mut buffer = vec![T::zero(); window_w * window_h];
+
+        let mut frame_buffer = FrameBuffer::new();
+        // `frame_buffer` is only used by the reads below
+        unsafe {
+            frame_buffer.insert_channel_with_fill(
+                channel,
+                &mut buffer,
+                window,
+                0.0,
+            )
+        }?;
+
+        match tiled_file.as_mut() {
+            Some(file) => {
+                let (dx1, dx2, dy1, dy2) = tile_range;
+                file.set_frame_buffer(&frame_buffer)?;
+                file.read_tiles(dx1, dx2, dy1, dy2, 0, 0)?;
+            }
+            None => {
+                // `buffer` only covers the rows of `region`, which are the
+                // only ones read
+                self.set_frame_buffer_unchecked(&frame_buffer)?;
+                let result = self.read_pixels(region[1], region[3]);
+                // don't leave the file pointing at `buffer` once it is gone,
+                // even if the read failed
+                self.set_frame_buffer(&FrameBuffer::new())?;
+                result?;
+            }
+        }
+
+        let region_w = (region[2] - region[0] + 1) as usize;
+        let x_offset = (region[0] - window[0]) as usize;
+        let y_offset = (region[1] - window[1]) as usize;
+        let mut result =
+            Vec::with_capacity(region_w * (region[3] - region[1] + 1) as usize);
+        for row in buffer
+            .chunks(window_w)
+            .skip(y_offset)
+            .take((region[3] - region[1] + 1) as usize)
+        {
+            result.extend_from_slice(&row[x_offset..x_offset + region_w]);
+        }
+
+        Ok(result)
+    }
+
+    /// Read the whole data window of `channel`, returning its width, height
+    /// and pixels with the rows running from top to bottom.
+    ///
+    /// OpenEXR already returns scanlines in pixel-space order whatever the
+    /// [`LineOrder`](crate::core::LineOrder) they were stored in, so files
+    /// written with [`LineOrder::DecreasingY`](crate::core::LineOrder) come
+    /// back the same way up as any other and no
+    /// [`remap_rows()`](crate::core::line_order::remap_rows) is applied. This
+    /// method guarantees that orientation so callers handing the pixels to
+    /// top-down image libraries don't have to reason about the line order.
+    ///
+    /// This replaces the current frame buffer of the `InputFile` with an
+    /// empty one, as [`read_region()`](InputFile::read_region) does.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `T` has more than one channel or
+    /// cannot hold the pixels of `channel` without losing data
+    /// * [`Error::Base`] - if any other error occurs
+    ///
+    pub fn read_channel_top_down<T: Pixel>(
+        &mut self,
+        channel: &str,
+    ) -> Result<(u32, u32, Vec<T>)> {
+        let dw = *self.header().data_window::<[i32; 4]>();
+        let pixels = self.read_region(dw, channel)?;
+        Ok((
+            (dw[2] as i64 - dw[0] as i64 + 1) as u32,
+            (dw[3] as i64 - dw[1] as i64 + 1) as u32,
+            pixels,
+        ))
+    }
+
+    /// Read every channel in the file into its own buffer whose type matches
+    /// the channel's [`PixelType`], keyed by channel name.
+    ///
+    /// Each buffer covers the whole data window, stored one row after
+    /// another. Subsampled channels only store their samples, so a channel
+    /// with an x sampling of 2 has half as many pixels per row.
+    ///
+    /// All channels are read with a single frame buffer in one pass over the
+    /// file. That frame buffer is replaced with an empty one afterwards, so
+    /// [`set_frame_buffer()`](InputFile::set_frame_buffer) must be called
+    /// again before any further calls to
+    /// [`read_pixels()`](InputFile::read_pixels).
+    ///
+    /// # Errors
+    /// * [`Error::InvalidType`] - if a channel has an unknown pixel type
+    /// * [`Error::Base`] - if any other error occurs
+    ///
+    pub fn read_all_channels(
+        &mut self,
+    ) -> Result<HashMap<String, ChannelBuffer>> {
+        let (dw, channels) = {
+            let header = self.header();
+            let channels = header
+                .channels()
+                .iter()
+                .map(|(name, c)| {
+                    (
+                        name.to_string(),
+                        PixelType::from(c.type_),
+                        c.x_sampling,
+                        c.y_sampling,
+                    )
+                })
+                .collect::<Vec<_>>();
+            (*header.data_window::<[i32; 4]>(), channels)
+        };
+
+        let mut buffers = HashMap::with_capacity(channels.len());
+        for (name, pixel_type, xs, ys) in &channels {
+            let (nx, ny) = sample_counts(dw, [*xs, *ys]);
+            buffers.insert(
+                name.clone(),
+                ChannelBuffer::zeroed(*pixel_type, nx * ny).ok_or_else(
+                    || {
+                        Error::InvalidType(format!(
+                            "channel \"{}\" has an unknown pixel type",
+                            name
+                        ))
+                    },
+                )?,
+            );
+        }
+
+        // the buffers are not resized after this, so their storage stays put
+        // until the read below
+        let mut frame_buffer = FrameBuffer::new();
+        for (name, _, xs, ys) in &channels {
+            buffers.get_mut(name).unwrap().insert_into_mut(
+                &mut frame_buffer,
+                name,
+                dw,
+                [*xs, *ys],
+            )?;
+        }
+
+        self.set_frame_buffer(&frame_buffer)?;
+        let result = self.read_pixels(dw[1], dw[3]);
+        // don't leave the file pointing at the buffers once they are returned
+        self.set_frame_buffer(&FrameBuffer::new())?;
+        result?;
+
+        Ok(buffers)
+    }
+
+    /// The path of the file
+    ///
+    fn file_name(&self) -> PathBuf {
+        let mut ptr = std::ptr::null();
+        unsafe {
+            sys::Imf_InputFile_fileName(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            cstr_to_path(CStr::from_ptr(ptr))
+        }
+    }
+
     /// Consume this `InputFile` and convert it to an [`InputFileReader`].
     ///
     /// The [`InputFileReader`] provides a safe API for reading data from the
@@ -185,6 +515,154 @@ impl InputFile {
     }
 }
 
+/// A summary of the layout of an image, as returned by [`InputFile::info()`]
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileInfo {
+    /// The width of the data window
+    pub width: i32,
+    /// The height of the data window
+    pub height: i32,
+    /// The name and pixel type of each channel, in alphabetical order
+    pub channels: Vec<(String, PixelType)>,
+    /// The compression applied to the pixel data
+    pub compression: Compression,
+    /// The order in which the scanlines are stored
+    pub line_order: LineOrder,
+}
+
+/// The pixels of a single channel, as returned by
+/// [`InputFile::read_all_channels()`]
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelBuffer {
+    U32(Vec<u32>),
+    Half(Vec<half::f16>),
+    Float(Vec<f32>),
+}
+
+impl ChannelBuffer {
+    /// Get the [`PixelType`] of the channel
+    ///
+    pub fn pixel_type(&self) -> PixelType {
+        match self {
+            ChannelBuffer::U32(_) => PixelType::Uint,
+            ChannelBuffer::Half(_) => PixelType::Half,
+            ChannelBuffer::Float(_) => PixelType::Float,
+        }
+    }
+
+    /// Get the number of pixels in the buffer
+    ///
+    pub fn len(&self) -> usize {
+        match self {
+            ChannelBuffer::U32(v) => v.len(),
+            ChannelBuffer::Half(v) => v.len(),
+            ChannelBuffer::Float(v) => v.len(),
+        }
+    }
+
+    /// Is the buffer empty?
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Create a buffer of `len` zeroes of `pixel_type`, or `None` if
+    /// `pixel_type` is not a real pixel type
+    ///
+    pub(crate) fn zeroed(pixel_type: PixelType, len: usize) -> Option<Self> {
+        match pixel_type {
+            PixelType::Uint => Some(ChannelBuffer::U32(vec![0; len])),
+            PixelType::Half => {
+                Some(ChannelBuffer::Half(vec![half::f16::ZERO; len]))
+            }
+            PixelType::Float => Some(ChannelBuffer::Float(vec![0.0; len])),
+            _ => None,
+        }
+    }
+
+    /// Insert a slice pointing at this buffer into `frame_buffer` as the
+    /// channel `name`, covering `data_window` with `sampling`, for writing.
+    ///
+    /// The buffer must be neither moved out of nor resized while
+    /// `frame_buffer` is in use.
+    ///
+    pub(crate) fn insert_into(
+        &self,
+        frame_buffer: &mut FrameBuffer,
+        name: &str,
+        data_window: [i32; 4],
+        sampling: [i32; 2],
+    ) -> Result<()> {
+        let data = match self {
+            ChannelBuffer::U32(v) => v.as_ptr() as *const u8,
+            ChannelBuffer::Half(v) => v.as_ptr() as *const u8,
+            ChannelBuffer::Float(v) => v.as_ptr() as *const u8,
+        };
+        self.insert_slice(frame_buffer, name, data, data_window, sampling)
+    }
+
+    /// As [`insert_into()`](ChannelBuffer::insert_into), but for reading into
+    /// the buffer.
+    ///
+    pub(crate) fn insert_into_mut(
+        &mut self,
+        frame_buffer: &mut FrameBuffer,
+        name: &str,
+        data_window: [i32; 4],
+        sampling: [i32; 2],
+    ) -> Result<()> {
+        let data = match self {
+            ChannelBuffer::U32(v) => v.as_mut_ptr() as *const u8,
+            ChannelBuffer::Half(v) => v.as_mut_ptr() as *const u8,
+            ChannelBuffer::Float(v) => v.as_mut_ptr() as *const u8,
+        };
+        self.insert_slice(frame_buffer, name, data, data_window, sampling)
+    }
+
+    fn insert_slice(
+        &self,
+        frame_buffer: &mut FrameBuffer,
+        name: &str,
+        data: *const u8,
+        data_window: [i32; 4],
+        sampling: [i32; 2],
+    ) -> Result<()> {
+        let stride = match self {
+            ChannelBuffer::U32(_) => u32::STRIDE,
+            ChannelBuffer::Half(_) => half::f16::STRIDE,
+            ChannelBuffer::Float(_) => f32::STRIDE,
+        };
+        let (nx, _) = sample_counts(data_window, sampling);
+
+        frame_buffer.insert(
+            name,
+            &Slice::with_data_window(self.pixel_type(), data, data_window)
+                .x_stride(stride)
+                .y_stride(stride * nx)
+                .x_sampling(sampling[0])
+                .y_sampling(sampling[1])
+                .build()?,
+        )
+    }
+}
+
+/// Get the number of samples in x and y of a channel with `sampling` that
+/// covers `data_window`
+///
+pub(crate) fn sample_counts(
+    data_window: [i32; 4],
+    sampling: [i32; 2],
+) -> (usize, usize) {
+    let [xs, ys] = sampling;
+    let dw = data_window;
+    (
+        (dw[2].div_euclid(xs) - dw[0].div_euclid(xs) + 1) as usize,
+        (dw[3].div_euclid(ys) - dw[1].div_euclid(ys) + 1) as usize,
+    )
+}
+
 /// `InputFileReader` provides a safe API over `InputFile` by taking ownership
 /// of the storage into which the channel data is to be read and handling all
 /// the [`Slice`](crate::core::frame_buffer::Slice) pointer offset shenanigans internally.
@@ -298,6 +776,32 @@ fn read_input_safe1() -> Result<()> {
     Ok(())
 }
 
+#[cfg(test)]
+#[test]
+fn input_file_is_complete1() -> Result<()> {
+    use std::path::PathBuf;
+
+    let path = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR not set"),
+    )
+    .join("images")
+    .join("ferris.exr");
+
+    let file = InputFile::new(&path, 4)?;
+    assert!(file.is_complete());
+
+    // Chop the end off the file so that the last chunks are missing
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::write("input_file_is_complete1.exr", &bytes[..bytes.len() / 2])
+        .unwrap();
+
+    let file = InputFile::new("input_file_is_complete1.exr", 4)?;
+    assert!(!file.is_complete());
+
+    Ok(())
+}
+
 #[cfg(all(test, feature = "impl_cgmath"))]
 #[test]
 fn read_input_safe_cgmath1() -> Result<()> {
@@ -361,3 +865,275 @@ fn read_input_safe_cgmath1() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+#[test]
+fn input_file_read_region1() -> Result<()> {
+    use half::f16;
+    use std::path::PathBuf;
+
+    let images = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR not set"),
+    )
+    .join("images");
+
+    for name in &["ferris.exr", "ferris-tiled.exr"] {
+        let mut file = InputFile::new(images.join(name), 1)?;
+        let dw = *file.header().data_window::<[i32; 4]>();
+        let width = (dw[2] - dw[0] + 1) as usize;
+
+        // read the whole channel to compare against
+        let mut full = vec![f16::ZERO; width * (dw[3] - dw[1] + 1) as usize];
+        let mut frame_buffer = FrameBuffer::new();
+        unsafe {
+            frame_buffer.insert_channel_with_fill("R", &mut full, dw, 0.0)
+        }?;
+        file.set_frame_buffer(&frame_buffer)?;
+        file.read_pixels(dw[1], dw[3])?;
+
+        let region = [dw[0] + 10, dw[1] + 20, dw[0] + 73, dw[1] + 41];
+        let crop = file.read_region::<f16>(region, "R")?;
+        assert_eq!(crop.len(), 64 * 22);
+        for y in region[1]..=region[3] {
+            for x in region[0]..=region[2] {
+                let c = crop[((y - region[1]) * 64 + x - region[0]) as usize];
+                let f =
+                    full[(y - dw[1]) as usize * width + (x - dw[0]) as usize];
+                assert_eq!(c, f, "{} differs at {}, {}", name, x, y);
+            }
+        }
+
+        assert!(file
+            .read_region::<f16>([dw[0] - 1, dw[1], dw[2], dw[3]], "R")
+            .is_err());
+
+        // "R" is half, which converts exactly to f32
+        let wide = file.read_region::<f32>(region, "R")?;
+        assert_eq!(wide.len(), crop.len());
+        for (w, c) in wide.iter().zip(crop.iter()) {
+            assert_eq!(*w, c.to_f32());
+        }
+        assert!(file.read_region::<f32>(region, "missing")?.len() == 64 * 22);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn input_file_read_all_channels1() -> Result<()> {
+    use half::f16;
+    use std::path::PathBuf;
+
+    let path = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR not set"),
+    )
+    .join("images")
+    .join("ferris.exr");
+
+    let mut file = InputFile::new(&path, 1)?;
+    let dw = *file.header().data_window::<[i32; 4]>();
+    let len = ((dw[2] - dw[0] + 1) * (dw[3] - dw[1] + 1)) as usize;
+
+    let channels = file.read_all_channels()?;
+    assert_eq!(channels.len(), file.header().channels().count());
+    for (name, buffer) in &channels {
+        assert_eq!(buffer.len(), len, "{} has the wrong length", name);
+        assert_eq!(
+            Some(buffer.pixel_type()),
+            file.header().channel_pixel_type(name)
+        );
+    }
+
+    // compare against reading the channel on its own
+    let mut r = vec![f16::ZERO; len];
+    let mut frame_buffer = FrameBuffer::new();
+    unsafe { frame_buffer.insert_channel_with_fill("R", &mut r, dw, 0.0) }?;
+    file.set_frame_buffer(&frame_buffer)?;
+    file.read_pixels(dw[1], dw[3])?;
+    assert_eq!(channels.get("R"), Some(&ChannelBuffer::Half(r)));
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn input_file_read_channel_top_down1() -> Result<()> {
+    use crate::{
+        core::{header::Header, LineOrder},
+        rgba::{rgba::RgbaChannels, rgba_file::RgbaOutputFile},
+        tests::load_ferris,
+    };
+    use half::f16;
+
+    let (pixels, width, height) = load_ferris();
+    let expected = pixels.iter().map(|p| p.r).collect::<Vec<_>>();
+
+    for (filename, line_order) in &[
+        (
+            "read_channel_top_down_increasing_y.exr",
+            LineOrder::IncreasingY,
+        ),
+        (
+            "read_channel_top_down_decreasing_y.exr",
+            LineOrder::DecreasingY,
+        ),
+    ] {
+        let mut header = Header::from_dimensions(width, height);
+        header.set_line_order(*line_order);
+        {
+            let mut file = RgbaOutputFile::new(
+                filename,
+                &header,
+                RgbaChannels::WriteRgba,
+                1,
+            )?;
+            file.set_frame_buffer(&pixels, 1, width as usize)?;
+            file.write_pixels(height)?;
+        }
+
+        let mut file = InputFile::new(filename, 1)?;
+        assert_eq!(file.header().line_order(), *line_order);
+        let (w, h, read) = file.read_channel_top_down::<f16>("R")?;
+        assert_eq!((w as i32, h as i32), (width, height));
+        assert!(read == expected, "{} is flipped", filename);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn input_file_set_frame_buffer_bounds1() -> Result<()> {
+    use half::f16;
+    use std::path::PathBuf;
+
+    let path = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR not set"),
+    )
+    .join("images")
+    .join("ferris.exr");
+
+    let mut file = InputFile::new(&path, 1)?;
+    let dw = *file.header().data_window::<[i32; 4]>();
+    let width = (dw[2] - dw[0] + 1) as usize;
+
+    // a buffer for only the first ten rows would be overrun by a full read
+    let short = [dw[0], dw[1], dw[2], dw[1] + 9];
+    let mut r = vec![f16::ZERO; width * 10];
+    let mut frame_buffer = FrameBuffer::new();
+    unsafe { frame_buffer.insert_channel_with_fill("R", &mut r, short, 0.0) }?;
+    assert!(matches!(
+        file.set_frame_buffer(&frame_buffer),
+        Err(Error::InvalidArgument(_))
+    ));
+
+    // the same goes for frames handed to into_reader()
+    let frame = Frame::new::<f16, _, _>(&["R"], short)?;
+    assert!(InputFile::new(&path, 1)?.into_reader(vec![frame]).is_err());
+
+    let frame = Frame::new::<f16, _, _>(&["R"], dw)?;
+    let (_, frames) = InputFile::new(&path, 1)?
+        .into_reader(vec![frame])?
+        .read_pixels(dw[1], dw[3])?;
+    assert_eq!(frames.len(), 1);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn input_file_info1() -> Result<()> {
+    let path = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR not set"),
+    )
+    .join("images")
+    .join("ferris.exr");
+
+    let file = InputFile::new(&path, 1)?;
+    let info = file.info();
+
+    let header = file.header();
+    let dw = header.data_window::<[i32; 4]>();
+    assert_eq!(info.width, dw[2] - dw[0] + 1);
+    assert_eq!(info.height, dw[3] - dw[1] + 1);
+    assert_eq!(info.compression, header.compression());
+    assert_eq!(info.line_order, header.line_order());
+    assert_eq!(
+        info.channels,
+        ["A", "B", "G", "R"]
+            .iter()
+            .map(|name| (name.to_string(), PixelType::Half))
+            .collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+#[test]
+fn input_file_non_utf8_path1() -> Result<()> {
+    use crate::core::{header::Header, output_file::OutputFile};
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut bytes =
+        format!("openexr-non-utf8-{}-", std::process::id()).into_bytes();
+    bytes.extend_from_slice(b"\xff\xfe.exr");
+    let path = std::env::temp_dir().join(OsStr::from_bytes(&bytes));
+    assert!(path.to_str().is_none());
+
+    let result = (|| -> Result<()> {
+        let header = Header::from_dimensions(4, 4);
+        let mut file = OutputFile::new(&path, &header, 1)?;
+        file.set_frame_buffer(&FrameBuffer::new())?;
+        unsafe {
+            file.write_pixels(4)?;
+        }
+        assert_eq!(file.file_name(), path);
+        file.finish()?;
+
+        let file = InputFile::new(&path, 1)?;
+        assert_eq!(file.info().width, 4);
+        Ok(())
+    })();
+    let _ = std::fs::remove_file(&path);
+    result?;
+
+    // tiled reads reopen the file by name
+    let tiled = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR not set"),
+    )
+    .join("images")
+    .join("ferris-tiled.exr");
+    std::fs::copy(&tiled, &path).map_err(|e| Error::Io(e.to_string()))?;
+
+    let result = (|| -> Result<()> {
+        let mut expected = InputFile::new(&tiled, 1)?;
+        let dw = *expected.header().data_window::<[i32; 4]>();
+        let region = [dw[0] + 10, dw[1] + 20, dw[0] + 73, dw[1] + 41];
+
+        let mut file = InputFile::new(&path, 1)?;
+        assert_eq!(file.file_name(), path);
+        assert_eq!(
+            file.read_region::<half::f16>(region, "R")?,
+            expected.read_region::<half::f16>(region, "R")?
+        );
+        Ok(())
+    })();
+    let _ = std::fs::remove_file(&path);
+    result?;
+
+    let path = std::env::temp_dir().join(OsStr::from_bytes(b"nul\0.exr"));
+    assert!(matches!(
+        InputFile::new(&path, 1),
+        Err(Error::InvalidArgument(_))
+    ));
+
+    Ok(())
+}
@@ -23,10 +23,19 @@ impl<'a> InputPart<'a> {
     /// Get an interface to the part `part_number` of the [`MultiPartInputFile`]
     /// `multi_part_file`.
     ///
+    /// # Errors
+    /// * [`Error::OutOfRange`] - if `part_number` does not index a part in the
+    /// file.
+    ///
     pub fn new(
         multi_part_file: &MultiPartInputFile,
         part_number: i32,
     ) -> Result<InputPart> {
+        // OpenEXR itself doesn't check this
+        if part_number < 0 || part_number >= multi_part_file.parts() {
+            return Err(Error::OutOfRange);
+        }
+
         let mut part = sys::Imf_InputPart_t::default();
         unsafe {
             sys::Imf_InputPart_ctor(&mut part, multi_part_file.0, part_number)
@@ -157,3 +166,24 @@ impl<'a> InputPart<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[test]
+fn input_part_new1() -> Result<()> {
+    use std::path::PathBuf;
+
+    let path = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR not set"),
+    )
+    .join("images")
+    .join("ferris-multipart.exr");
+
+    let file = MultiPartInputFile::new(path, 4, true)?;
+
+    assert_eq!(InputPart::new(&file, 1)?.header().name()?, "right");
+    assert!(matches!(InputPart::new(&file, 2), Err(Error::OutOfRange)));
+    assert!(matches!(InputPart::new(&file, -1), Err(Error::OutOfRange)));
+
+    Ok(())
+}
@@ -96,9 +96,9 @@ impl KeyCode {
     pub fn prefix(&self) -> i32 {
         let mut v = 0i32;
         unsafe {
-            sys::Imf_KeyCode_filmType(&self.0, &mut v)
+            sys::Imf_KeyCode_prefix(&self.0, &mut v)
                 .into_result()
-                .expect("Unexpected exception from Imf_KeyCode_filmType");
+                .expect("Unexpected exception from Imf_KeyCode_prefix");
         }
         v
     }
@@ -122,9 +122,9 @@ impl KeyCode {
     pub fn count(&self) -> i32 {
         let mut v = 0i32;
         unsafe {
-            sys::Imf_KeyCode_filmType(&self.0, &mut v)
+            sys::Imf_KeyCode_count(&self.0, &mut v)
                 .into_result()
-                .expect("Unexpected exception from Imf_KeyCode_filmType");
+                .expect("Unexpected exception from Imf_KeyCode_count");
         }
         v
     }
@@ -148,9 +148,9 @@ impl KeyCode {
     pub fn perf_offset(&self) -> i32 {
         let mut v = 0i32;
         unsafe {
-            sys::Imf_KeyCode_filmType(&self.0, &mut v)
+            sys::Imf_KeyCode_perfOffset(&self.0, &mut v)
                 .into_result()
-                .expect("Unexpected exception from Imf_KeyCode_filmType");
+                .expect("Unexpected exception from Imf_KeyCode_perfOffset");
         }
         v
     }
@@ -180,9 +180,9 @@ impl KeyCode {
     pub fn perfs_per_frame(&self) -> i32 {
         let mut v = 0i32;
         unsafe {
-            sys::Imf_KeyCode_filmType(&self.0, &mut v)
+            sys::Imf_KeyCode_perfsPerFrame(&self.0, &mut v)
                 .into_result()
-                .expect("Unexpected exception from Imf_KeyCode_filmType");
+                .expect("Unexpected exception from Imf_KeyCode_perfsPerFrame");
         }
         v
     }
@@ -217,9 +217,9 @@ impl KeyCode {
     pub fn perfs_per_count(&self) -> i32 {
         let mut v = 0i32;
         unsafe {
-            sys::Imf_KeyCode_filmType(&self.0, &mut v)
+            sys::Imf_KeyCode_perfsPerCount(&self.0, &mut v)
                 .into_result()
-                .expect("Unexpected exception from Imf_KeyCode_filmType");
+                .expect("Unexpected exception from Imf_KeyCode_perfsPerCount");
         }
         v
     }
@@ -256,6 +256,55 @@ impl Default for KeyCode {
     }
 }
 
+impl Clone for KeyCode {
+    fn clone(&self) -> Self {
+        let mut inner = sys::Imf_KeyCode_t::default();
+        unsafe {
+            sys::Imf_KeyCode_ctor(
+                &mut inner,
+                self.film_mfc_code(),
+                self.film_type(),
+                self.prefix(),
+                self.count(),
+                self.perf_offset(),
+                self.perfs_per_frame(),
+                self.perfs_per_count(),
+            )
+            .into_result()
+            .expect("Unexpected exception from Imf_KeyCode_ctor");
+        }
+        KeyCode(inner)
+    }
+}
+
+impl PartialEq for KeyCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.film_mfc_code() == other.film_mfc_code()
+            && self.film_type() == other.film_type()
+            && self.prefix() == other.prefix()
+            && self.count() == other.count()
+            && self.perf_offset() == other.perf_offset()
+            && self.perfs_per_frame() == other.perfs_per_frame()
+            && self.perfs_per_count() == other.perfs_per_count()
+    }
+}
+
+impl Eq for KeyCode {}
+
+impl std::fmt::Debug for KeyCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyCode")
+            .field("film_mfc_code", &self.film_mfc_code())
+            .field("film_type", &self.film_type())
+            .field("prefix", &self.prefix())
+            .field("count", &self.count())
+            .field("perf_offset", &self.perf_offset())
+            .field("perfs_per_frame", &self.perfs_per_frame())
+            .field("perfs_per_count", &self.perfs_per_count())
+            .finish()
+    }
+}
+
 #[cfg(test)]
 #[test]
 fn test_keycode() {
@@ -265,3 +314,26 @@ fn test_keycode() {
     assert!(k.set_film_mfc_code(1).is_ok());
     assert_eq!(k.film_mfc_code(), 1);
 }
+
+#[cfg(test)]
+#[test]
+fn test_keycode_fields() {
+    let mut k = KeyCode::default();
+    k.set_film_mfc_code(1).unwrap();
+    k.set_film_type(2).unwrap();
+    k.set_prefix(3).unwrap();
+    k.set_count(4).unwrap();
+    k.set_perf_offset(5).unwrap();
+    k.set_perfs_per_frame(8).unwrap();
+    k.set_perfs_per_count(80).unwrap();
+
+    assert_eq!(k.prefix(), 3);
+    assert_eq!(k.count(), 4);
+    assert_eq!(k.perf_offset(), 5);
+    assert_eq!(k.perfs_per_frame(), 8);
+    assert_eq!(k.perfs_per_count(), 80);
+
+    let c = k.clone();
+    assert_eq!(c, k);
+    assert_ne!(c, KeyCode::default());
+}
@@ -0,0 +1,163 @@
+use crate::core::{error::Error, LineOrder};
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Reorder the rows of `data` so that they run from top to bottom, given
+/// that they are currently stored in the given [`LineOrder`].
+///
+/// `data` is a `width` by `height` image stored one row after another. For
+/// [`LineOrder::DecreasingY`] the rows are flipped in place, and for
+/// [`LineOrder::IncreasingY`] this is a no-op.
+///
+/// Note that the scanline readers such as
+/// [`InputFile`](crate::core::input_file::InputFile) always return rows in
+/// pixel-space (top to bottom) order whatever the line order of the file, so
+/// this is only needed for data that is still in the order it was stored in,
+/// e.g. scanlines collected in the order they were written.
+///
+/// # Errors
+/// * [`Error::InvalidArgument`] - if `order` is [`LineOrder::RandomY`], for
+/// which the row order is undefined, or if `data` does not contain exactly
+/// `width * height` elements.
+///
+pub fn remap_rows<T>(
+    data: &mut [T],
+    width: usize,
+    height: usize,
+    order: LineOrder,
+) -> Result<()> {
+    if data.len() != width * height {
+        return Err(Error::InvalidArgument(format!(
+            "data has {} elements but width * height is {}",
+            data.len(),
+            width * height
+        )));
+    }
+
+    match order {
+        LineOrder::IncreasingY => Ok(()),
+        LineOrder::DecreasingY => {
+            if width > 0 {
+                for y in 0..height / 2 {
+                    let (top, bottom) =
+                        data.split_at_mut((height - 1 - y) * width);
+                    top[y * width..(y + 1) * width]
+                        .swap_with_slice(&mut bottom[..width]);
+                }
+            }
+            Ok(())
+        }
+        _ => Err(Error::InvalidArgument(format!(
+            "Cannot remap rows with line order {:?}",
+            order
+        ))),
+    }
+}
+
+/// Get all the [`LineOrder`]s supported by the bound OpenEXR library, in the
+/// order they are numbered in the file format.
+///
+pub fn all() -> &'static [LineOrder] {
+    &[
+        LineOrder::IncreasingY,
+        LineOrder::DecreasingY,
+        LineOrder::RandomY,
+    ]
+}
+
+#[cfg(test)]
+#[test]
+fn test_remap_rows() {
+    let mut data = vec![0, 1, 2, 3, 4, 5, 6, 7, 8];
+    remap_rows(&mut data, 3, 3, LineOrder::IncreasingY).unwrap();
+    assert_eq!(data, [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+    remap_rows(&mut data, 3, 3, LineOrder::DecreasingY).unwrap();
+    assert_eq!(data, [6, 7, 8, 3, 4, 5, 0, 1, 2]);
+
+    let mut data = vec![0, 1, 2, 3, 4, 5, 6, 7];
+    remap_rows(&mut data, 2, 4, LineOrder::DecreasingY).unwrap();
+    assert_eq!(data, [6, 7, 4, 5, 2, 3, 0, 1]);
+
+    assert!(remap_rows(&mut data, 2, 4, LineOrder::RandomY).is_err());
+    assert!(remap_rows(&mut data, 3, 3, LineOrder::IncreasingY).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_remap_rows_decreasing_y_file() -> Result<()> {
+    use crate::{
+        core::header::Header,
+        rgba::{
+            rgba::{Rgba, RgbaChannels},
+            rgba_file::{RgbaInputFile, RgbaOutputFile},
+        },
+        tests::load_ferris,
+    };
+    use imath_traits::Zero;
+
+    let (pixels, width, height) = load_ferris();
+
+    for (filename, line_order) in &[
+        ("remap_rows_increasing_y.exr", LineOrder::IncreasingY),
+        ("remap_rows_decreasing_y.exr", LineOrder::DecreasingY),
+    ] {
+        let mut header = Header::from_dimensions(width, height);
+        header.set_line_order(*line_order);
+        let mut file =
+            RgbaOutputFile::new(filename, &header, RgbaChannels::WriteRgba, 1)?;
+        file.set_frame_buffer(&pixels, 1, width as usize)?;
+        file.write_pixels(height)?;
+    }
+
+    let mut result = Vec::new();
+    for filename in
+        &["remap_rows_increasing_y.exr", "remap_rows_decreasing_y.exr"]
+    {
+        let mut file = RgbaInputFile::new(filename, 1)?;
+        let line_order = file.header().line_order();
+        let mut read = vec![Rgba::zero(); (width * height) as usize];
+        file.set_frame_buffer(&mut read, 1, width as usize)?;
+        file.read_pixels(0, height - 1)?;
+        result.push((line_order, read));
+    }
+
+    // The reader has already put both images in pixel-space order...
+    assert_eq!(result[0].0, LineOrder::IncreasingY);
+    assert_eq!(result[1].0, LineOrder::DecreasingY);
+    assert!(result[0].1 == result[1].1);
+
+    // ...so flipping the rows of one gives the same result as flipping the
+    // other.
+    let mut a = result[0].1.clone();
+    let mut b = result[1].1.clone();
+    remap_rows(
+        &mut a,
+        width as usize,
+        height as usize,
+        LineOrder::DecreasingY,
+    )?;
+    remap_rows(
+        &mut b,
+        width as usize,
+        height as usize,
+        LineOrder::DecreasingY,
+    )?;
+    assert!(a == b);
+    assert!(a != result[0].1);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_all() {
+    assert_eq!(
+        all(),
+        &[
+            LineOrder::IncreasingY,
+            LineOrder::DecreasingY,
+            LineOrder::RandomY
+        ]
+    );
+}
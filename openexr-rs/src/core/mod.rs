@@ -1,14 +1,18 @@
 pub mod attribute;
 pub mod channel_list;
+pub mod chromaticities;
+pub mod compression;
 pub mod cppstd;
 pub mod envmap;
 pub mod error;
 pub mod frame_buffer;
 pub mod frames_per_second;
 pub mod header;
+pub mod imath;
 pub mod input_file;
 pub mod input_part;
 pub mod keycode;
+pub mod line_order;
 pub mod multi_view;
 pub mod output_file;
 pub mod output_part;
@@ -1,16 +1,19 @@
 use openexr_sys as sys;
 
-use std::ffi::CString;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use crate::core::{
     error::Error,
     frame_buffer::{FrameBuffer, FrameBufferRef},
     header::{Header, HeaderRef},
-    input_file::InputFile,
+    input_file::{sample_counts, ChannelBuffer, InputFile},
     input_part::InputPart,
     preview_image::PreviewRgba,
+    PixelType,
 };
+use crate::rgba::rgba_file::RgbaInputFile;
+use crate::util::{cstr_to_path, path_to_cstring};
 
 type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -23,24 +26,23 @@ impl OutputFile {
     /// The file header is also copied into the OutputFile object,
     /// and can later be accessed via the `header()` method.
     /// Dropping this OutputFile object automatically closes
-    /// the file.
+    /// the file, but call [`OutputFile::finish()`] to find out whether
+    /// closing it succeeded.
+    ///
+    /// On Unix the path is passed to OpenEXR as raw bytes, so it does not
+    /// need to be valid UTF-8.
     ///
     /// # Errors
     /// * [`Error::Base`] - If the file could not be opened or the [`Header`]
     /// is invalid
+    /// * [`Error::InvalidArgument`] - If `filename` contains a NUL byte
     ///
     pub fn new<P: AsRef<Path>>(
         filename: P,
         header: &Header,
         num_threads: i32,
     ) -> Result<OutputFile> {
-        let c_filename = CString::new(
-            filename
-                .as_ref()
-                .to_str()
-                .expect("Invalid bytes in filename"),
-        )
-        .expect("Internal null bytes in filename");
+        let c_filename = path_to_cstring(filename.as_ref())?;
 
         let mut _inner = std::ptr::null_mut();
         unsafe {
@@ -58,15 +60,13 @@ impl OutputFile {
 
     /// Get the filename this file is writing to.
     ///
-    pub fn file_name(&self) -> &str {
+    pub fn file_name(&self) -> PathBuf {
         unsafe {
             let mut ptr = std::ptr::null();
             sys::Imf_OutputFile_fileName(self.0, &mut ptr)
                 .into_result()
                 .unwrap();
-            std::ffi::CStr::from_ptr(ptr)
-                .to_str()
-                .expect("Invalid UTF-8")
+            cstr_to_path(std::ffi::CStr::from_ptr(ptr))
         }
     }
 
@@ -158,6 +158,138 @@ impl OutputFile {
         Ok(())
     }
 
+    /// Write every channel of the image from `channels`, keyed by channel
+    /// name, as returned by
+    /// [`InputFile::read_all_channels()`](crate::core::input_file::InputFile::read_all_channels).
+    ///
+    /// Each buffer must cover the whole data window, stored one row after
+    /// another, with only the samples of subsampled channels.
+    ///
+    /// All scan lines are written, so this must be called before any calls to
+    /// [`write_pixels()`](OutputFile::write_pixels). The frame buffer is
+    /// replaced with an empty one afterwards.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - If `channels` is missing any channel of
+    /// the header or has channels that are not in the header, or if any
+    /// buffer is the wrong length.
+    /// * [`Error::InvalidType`] - If the pixel type of any buffer does not
+    /// match that of its channel in the header.
+    /// * [`Error::Io`] - If an I/O error occurs while writing the image data
+    /// * [`Error::Base`] - If any other error occurs.
+    ///
+    pub fn write_all_channels(
+        &mut self,
+        channels: &HashMap<String, ChannelBuffer>,
+    ) -> Result<()> {
+        let (dw, header_channels) = {
+            let header = self.header();
+            let header_channels = header
+                .channels()
+                .iter()
+                .map(|(name, c)| {
+                    (
+                        name.to_string(),
+                        c.type_.into(),
+                        c.x_sampling,
+                        c.y_sampling,
+                    )
+                })
+                .collect::<Vec<(String, PixelType, i32, i32)>>();
+            (*header.data_window::<[i32; 4]>(), header_channels)
+        };
+
+        if let Some(name) = channels
+            .keys()
+            .find(|name| !header_channels.iter().any(|c| &c.0 == *name))
+        {
+            return Err(Error::InvalidArgument(format!(
+                "channel \"{}\" is not in the header",
+                name
+            )));
+        }
+
+        let mut frame_buffer = FrameBuffer::new();
+        for (name, pixel_type, xs, ys) in &header_channels {
+            let buffer = channels.get(name).ok_or_else(|| {
+                Error::InvalidArgument(format!(
+                    "no buffer for channel \"{}\"",
+                    name
+                ))
+            })?;
+
+            if buffer.pixel_type() != *pixel_type {
+                return Err(Error::InvalidType(format!(
+                    "channel \"{}\" is {:?} but its buffer is {:?}",
+                    name,
+                    pixel_type,
+                    buffer.pixel_type()
+                )));
+            }
+
+            let (nx, ny) = sample_counts(dw, [*xs, *ys]);
+            if buffer.len() != nx * ny {
+                return Err(Error::InvalidArgument(format!(
+                    "buffer for channel \"{}\" has {} pixels, expected {}",
+                    name,
+                    buffer.len(),
+                    nx * ny
+                )));
+            }
+
+            buffer.insert_into(&mut frame_buffer, name, dw, [*xs, *ys])?;
+        }
+
+        self.set_frame_buffer(&frame_buffer)?;
+        // Safe as every slice was checked to cover the data window above
+        let result = unsafe { self.write_pixels(dw[3] - dw[1] + 1) };
+        // don't leave the file pointing at `channels` once it is released
+        self.set_frame_buffer(&FrameBuffer::new())?;
+        result
+    }
+
+    /// Write the whole image from `frame_buffer`, whose rows run from top to
+    /// bottom, so that the file displays the right way up whatever the
+    /// header's [`LineOrder`](crate::core::LineOrder).
+    ///
+    /// OpenEXR fetches each scan line from the frame buffer by its y
+    /// coordinate, and for
+    /// [`LineOrder::DecreasingY`](crate::core::LineOrder) it does so from the
+    /// bottom of the data window upwards. Top-down data therefore needs no
+    /// flipping, and this method writes all the scan lines in the order the
+    /// header's line order requires.
+    ///
+    /// This must be called before any calls to
+    /// [`write_pixels()`](OutputFile::write_pixels). The frame buffer is
+    /// replaced with an empty one afterwards.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - If a channel inserted with
+    /// [`FrameBuffer::insert_channel_with_fill()`] or as part of a
+    /// [`Frame`](crate::core::frame_buffer::Frame) does not cover the data
+    /// window.
+    /// * [`Error::Io`] - If an I/O error occurs while writing the image data
+    /// * [`Error::Base`] - If any other error occurs.
+    ///
+    /// # Safety
+    /// As for [`write_pixels()`](OutputFile::write_pixels), every slice
+    /// inserted directly with [`FrameBuffer::insert()`] must point to
+    /// storage that covers the data window.
+    ///
+    pub unsafe fn write_pixels_top_down(
+        &mut self,
+        frame_buffer: &FrameBuffer,
+    ) -> Result<()> {
+        let dw = *self.header().data_window::<[i32; 4]>();
+        frame_buffer.check_covers(&dw)?;
+
+        self.set_frame_buffer(frame_buffer)?;
+        let result = self.write_pixels(dw[3] - dw[1] + 1);
+        // don't leave the file pointing at `frame_buffer` once it is released
+        self.set_frame_buffer(&FrameBuffer::new())?;
+        result
+    }
+
     /// Access to the current scan line:
     ///
     /// Returns the y coordinate of the first scan line
@@ -256,12 +388,51 @@ impl OutputFile {
 
         Ok(())
     }
+
+    /// Close the file, returning an error if it could not be finalized.
+    ///
+    /// OpenEXR writes the table of scan line offsets when the file is closed,
+    /// and ignores any error that occurs while doing so (e.g. because the
+    /// disk is full). Simply dropping the [`OutputFile`] can therefore leave
+    /// a damaged file behind without any indication. This method closes the
+    /// file, then re-opens it to check that every scan line in the data window
+    /// is present.
+    ///
+    /// # Errors
+    /// * [`Error::Io`] - If the written file is incomplete, for instance
+    /// because not all scan lines were written or the offset table could not
+    /// be written
+    /// * [`Error::Base`] - If the written file cannot be opened again
+    ///
+    pub fn finish(self) -> Result<()> {
+        let file_name = self.file_name();
+
+        let ptr = self.0;
+        std::mem::forget(self);
+        unsafe {
+            sys::Imf_OutputFile_dtor(ptr).into_result()?;
+        }
+
+        if RgbaInputFile::new(&file_name, 1)?.is_complete() {
+            Ok(())
+        } else {
+            Err(Error::Io(format!(
+                "\"{}\" is incomplete",
+                file_name.display()
+            )))
+        }
+    }
 }
 
 impl Drop for OutputFile {
+    /// Closes the file. Any error is printed to stderr rather than reported,
+    /// so call [`OutputFile::finish()`] instead to handle it.
+    ///
     fn drop(&mut self) {
         unsafe {
-            sys::Imf_OutputFile_dtor(self.0);
+            if let Err(e) = sys::Imf_OutputFile_dtor(self.0).into_result() {
+                eprintln!("Error closing OutputFile: {}", Error::from(e));
+            }
         }
     }
 }
@@ -502,3 +673,166 @@ fn write_gz2() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+#[test]
+fn write_outputfile_finish1() -> Result<()> {
+    use crate::core::{channel_list::CHANNEL_HALF, frame_buffer::Frame};
+    use crate::tests::load_ferris;
+
+    let (pixels, width, height) = load_ferris();
+
+    let mut header = Header::from_dimensions(width, height);
+
+    let channel_names = ["R", "G", "B", "A"];
+    for c in &channel_names {
+        header.channels_mut().insert(c, &CHANNEL_HALF);
+    }
+
+    let mut frame_buffer = FrameBuffer::new();
+    let frame = Frame::with_vec(
+        &channel_names,
+        pixels,
+        *header.data_window::<[i32; 4]>(),
+    )?;
+    frame_buffer.insert_frame(frame)?;
+
+    let mut file = OutputFile::new("write_outputfile_finish1.exr", &header, 1)?;
+    file.set_frame_buffer(&frame_buffer)?;
+    unsafe { file.write_pixels(height)? };
+    file.finish()?;
+
+    // stopping half way leaves an incomplete file, which finish() reports
+    let mut file = OutputFile::new("write_outputfile_finish2.exr", &header, 1)?;
+    file.set_frame_buffer(&frame_buffer)?;
+    unsafe { file.write_pixels(height / 2)? };
+    assert!(matches!(file.finish(), Err(Error::Io(_))));
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn write_outputfile_write_all_channels1() -> Result<()> {
+    use crate::core::channel_list::{
+        CHANNEL_FLOAT, CHANNEL_HALF, CHANNEL_UINT,
+    };
+    use half::f16;
+
+    let (width, height) = (16, 8);
+    let len = (width * height) as usize;
+    let mut header = Header::from_dimensions(width, height);
+    header.channels_mut().insert("R", &CHANNEL_HALF);
+    header.channels_mut().insert("Z", &CHANNEL_FLOAT);
+    header.channels_mut().insert("id", &CHANNEL_UINT);
+
+    let mut channels = HashMap::new();
+    channels.insert(
+        "R".to_string(),
+        ChannelBuffer::Half(
+            (0..len).map(|i| f16::from_f32(i as f32 / 8.0)).collect(),
+        ),
+    );
+    channels.insert(
+        "Z".to_string(),
+        ChannelBuffer::Float((0..len).map(|i| i as f32 * 1.5).collect()),
+    );
+    channels.insert(
+        "id".to_string(),
+        ChannelBuffer::U32((0..len as u32).collect()),
+    );
+
+    {
+        let mut file = OutputFile::new(
+            "write_outputfile_write_all_channels1.exr",
+            &header,
+            1,
+        )?;
+
+        // mismatched channels are rejected before anything is written
+        let mut wrong_type = channels.clone();
+        wrong_type.insert("Z".to_string(), ChannelBuffer::U32(vec![0; len]));
+        assert!(file.write_all_channels(&wrong_type).is_err());
+        let mut extra = channels.clone();
+        extra
+            .insert("G".to_string(), ChannelBuffer::Half(vec![f16::ZERO; len]));
+        assert!(file.write_all_channels(&extra).is_err());
+        let mut missing = channels.clone();
+        missing.remove("id");
+        assert!(file.write_all_channels(&missing).is_err());
+
+        file.write_all_channels(&channels)?;
+    }
+
+    let mut file =
+        InputFile::new("write_outputfile_write_all_channels1.exr", 1)?;
+    assert_eq!(file.read_all_channels()?, channels);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn write_outputfile_write_pixels_top_down1() -> Result<()> {
+    use crate::{
+        core::{channel_list::CHANNEL_HALF, LineOrder},
+        tests::load_ferris,
+    };
+    use half::f16;
+
+    let (pixels, width, height) = load_ferris();
+    let mut expected = pixels.iter().map(|p| p.r).collect::<Vec<_>>();
+
+    for (filename, line_order) in &[
+        (
+            "write_pixels_top_down_increasing_y.exr",
+            LineOrder::IncreasingY,
+        ),
+        (
+            "write_pixels_top_down_decreasing_y.exr",
+            LineOrder::DecreasingY,
+        ),
+    ] {
+        let mut header = Header::from_dimensions(width, height);
+        header.channels_mut().insert("R", &CHANNEL_HALF);
+        header.set_line_order(*line_order);
+        let dw = *header.data_window::<[i32; 4]>();
+
+        {
+            let mut file = OutputFile::new(filename, &header, 1)?;
+
+            let mut short = vec![f16::ZERO; width as usize];
+            let mut frame_buffer = FrameBuffer::new();
+            unsafe {
+                frame_buffer.insert_channel_with_fill(
+                    "R",
+                    &mut short,
+                    [dw[0], dw[1], dw[2], dw[1]],
+                    0.0,
+                )
+            }?;
+            assert!(
+                unsafe { file.write_pixels_top_down(&frame_buffer) }.is_err()
+            );
+
+            let mut frame_buffer = FrameBuffer::new();
+            unsafe {
+                frame_buffer.insert_channel_with_fill(
+                    "R",
+                    &mut expected,
+                    dw,
+                    0.0,
+                )
+            }?;
+            unsafe {
+                file.write_pixels_top_down(&frame_buffer)?;
+            }
+        }
+
+        let mut file = InputFile::new(filename, 1)?;
+        let (_, _, read) = file.read_channel_top_down::<f16>("R")?;
+        assert!(read == expected, "{} is flipped", filename);
+    }
+
+    Ok(())
+}
@@ -26,10 +26,19 @@ impl<'a> OutputPart<'a> {
     /// Get an interface to the part `part_number` of the [`MultiPartOutputFile`]
     /// `multi_part_file`.
     ///
+    /// # Errors
+    /// * [`Error::OutOfRange`] - if `part_number` does not index a part in the
+    /// file.
+    ///
     pub fn new(
         multi_part_file: &MultiPartOutputFile,
         part_number: i32,
     ) -> Result<OutputPart> {
+        // OpenEXR itself doesn't check this
+        if part_number < 0 || part_number >= multi_part_file.parts() {
+            return Err(Error::OutOfRange);
+        }
+
         let mut part = sys::Imf_OutputPart_t::default();
         unsafe {
             sys::Imf_OutputPart_ctor(&mut part, multi_part_file.0, part_number)
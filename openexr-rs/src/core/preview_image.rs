@@ -1,7 +1,17 @@
 use openexr_sys as sys;
 
-use crate::core::refptr::{OpaquePtr, Ref, RefMut};
+use crate::core::{
+    error::Error,
+    refptr::{OpaquePtr, Ref, RefMut},
+};
+use crate::rgba::rgba::Rgba;
 
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A preview image is a small, 8-bit version of an image that can be stored
+/// in the header so that applications can display a thumbnail quickly without
+/// reading the full image.
+///
 #[repr(transparent)]
 pub struct PreviewImage(pub(crate) *mut sys::Imf_PreviewImage_t);
 
@@ -13,11 +23,443 @@ unsafe impl OpaquePtr for PreviewImage {
 pub type PreviewImageRef<'a, P = PreviewImage> = Ref<'a, P>;
 pub type PreviewImageRefMut<'a, P = PreviewImage> = RefMut<'a, P>;
 
+impl PreviewImage {
+    /// Create a new preview image of `width` by `height` pixels, copying the
+    /// pixels from `pixels`.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `pixels` does not contain exactly
+    /// `width * height` elements
+    ///
+    pub fn new(
+        width: u32,
+        height: u32,
+        pixels: &[PreviewRgba],
+    ) -> Result<PreviewImage> {
+        if pixels.len() != width as usize * height as usize {
+            return Err(Error::InvalidArgument(format!(
+                "pixels has {} elements but width * height is {}",
+                pixels.len(),
+                width as usize * height as usize
+            )));
+        }
+
+        let mut ptr = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_PreviewImage_ctor(
+                &mut ptr,
+                width,
+                height,
+                pixels.as_ptr() as *const sys::Imf_PreviewRgba_t,
+            )
+            .into_result()?;
+        }
+
+        Ok(PreviewImage(ptr))
+    }
+
+    /// Create a preview image from a buffer of `width` by `height`
+    /// half-float [`Rgba`] pixels.
+    ///
+    /// The image is downsampled so that its largest dimension is at most
+    /// `target_max_dim` pixels, then tone-mapped to 8 bits in the same way as
+    /// OpenEXR's `exrmakepreview`.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `pixels` does not contain exactly
+    /// `width * height` elements, or if any of `width`, `height` or
+    /// `target_max_dim` is zero
+    ///
+    pub fn from_rgba_f16(
+        width: usize,
+        height: usize,
+        pixels: &[Rgba],
+        target_max_dim: usize,
+    ) -> Result<PreviewImage> {
+        check_preview_args(width, height, pixels.len(), target_max_dim)?;
+        make_preview(width, height, target_max_dim, |i| {
+            let p = &pixels[i];
+            [p.r.to_f32(), p.g.to_f32(), p.b.to_f32(), p.a.to_f32()]
+        })
+    }
+
+    /// Create a preview image from a buffer of `width` by `height` RGBA
+    /// `f32` pixels.
+    ///
+    /// The image is downsampled so that its largest dimension is at most
+    /// `target_max_dim` pixels, then tone-mapped to 8 bits in the same way as
+    /// OpenEXR's `exrmakepreview`.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `pixels` does not contain exactly
+    /// `width * height` elements, or if any of `width`, `height` or
+    /// `target_max_dim` is zero
+    ///
+    pub fn from_rgba_f32(
+        width: usize,
+        height: usize,
+        pixels: &[[f32; 4]],
+        target_max_dim: usize,
+    ) -> Result<PreviewImage> {
+        check_preview_args(width, height, pixels.len(), target_max_dim)?;
+        make_preview(width, height, target_max_dim, |i| pixels[i])
+    }
+
+    /// Get the width of the preview image
+    ///
+    pub fn width(&self) -> u32 {
+        let mut v = 0;
+        unsafe {
+            sys::Imf_PreviewImage_width(self.0, &mut v)
+                .into_result()
+                .unwrap();
+        }
+        v
+    }
+
+    /// Get the height of the preview image
+    ///
+    pub fn height(&self) -> u32 {
+        let mut v = 0;
+        unsafe {
+            sys::Imf_PreviewImage_height(self.0, &mut v)
+                .into_result()
+                .unwrap();
+        }
+        v
+    }
+
+    /// Get the pixels of the preview image, stored one row after another
+    ///
+    pub fn pixels(&self) -> &[PreviewRgba] {
+        let mut ptr = std::ptr::null();
+        unsafe {
+            sys::Imf_PreviewImage_pixels_const(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            if ptr.is_null() {
+                &[]
+            } else {
+                std::slice::from_raw_parts(
+                    ptr as *const PreviewRgba,
+                    self.width() as usize * self.height() as usize,
+                )
+            }
+        }
+    }
+
+    /// Get the pixels of the preview image mutably, stored one row after
+    /// another
+    ///
+    pub fn pixels_mut(&mut self) -> &mut [PreviewRgba] {
+        let mut ptr = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_PreviewImage_pixels(self.0, &mut ptr)
+                .into_result()
+                .unwrap();
+            if ptr.is_null() {
+                &mut []
+            } else {
+                std::slice::from_raw_parts_mut(
+                    ptr as *mut PreviewRgba,
+                    self.width() as usize * self.height() as usize,
+                )
+            }
+        }
+    }
+}
+
+impl Drop for PreviewImage {
+    fn drop(&mut self) {
+        unsafe {
+            sys::Imf_PreviewImage_dtor(self.0);
+        }
+    }
+}
+
+impl Clone for PreviewImage {
+    /// Deep-copies the pixels, so the clone can be modified or set on another
+    /// header without affecting `self`
+    ///
+    fn clone(&self) -> Self {
+        let mut ptr = std::ptr::null_mut();
+        unsafe {
+            sys::Imf_PreviewImage_copy(&mut ptr, self.0)
+                .into_result()
+                .expect("Failed to copy preview image");
+        }
+
+        PreviewImage(ptr)
+    }
+}
+
+impl PartialEq for PreviewImage {
+    /// Two preview images are equal if they have the same dimensions and
+    /// pixels
+    ///
+    fn eq(&self, other: &PreviewImage) -> bool {
+        self.width() == other.width()
+            && self.height() == other.height()
+            && self.pixels() == other.pixels()
+    }
+}
+
+impl Eq for PreviewImage {}
+
+fn check_preview_args(
+    width: usize,
+    height: usize,
+    len: usize,
+    target_max_dim: usize,
+) -> Result<()> {
+    if width == 0 || height == 0 || target_max_dim == 0 {
+        Err(Error::InvalidArgument(format!(
+            "width ({}), height ({}) and target_max_dim ({}) must be non-zero",
+            width, height, target_max_dim
+        )))
+    } else if len != width * height {
+        Err(Error::InvalidArgument(format!(
+            "pixels has {} elements but width * height is {}",
+            len,
+            width * height
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Box-filter the image given by `fetch` down to at most `target_max_dim` on
+/// its longest side, then tone map it to 8 bits.
+///
+fn make_preview<F>(
+    width: usize,
+    height: usize,
+    target_max_dim: usize,
+    fetch: F,
+) -> Result<PreviewImage>
+where
+    F: Fn(usize) -> [f32; 4],
+{
+    let (pw, ph) = if width.max(height) <= target_max_dim {
+        (width, height)
+    } else if width >= height {
+        (
+            target_max_dim,
+            ((height * target_max_dim + width / 2) / width).max(1),
+        )
+    } else {
+        (
+            ((width * target_max_dim + height / 2) / height).max(1),
+            target_max_dim,
+        )
+    };
+
+    let mut preview = Vec::with_capacity(pw * ph);
+    for py in 0..ph {
+        let y0 = py * height / ph;
+        let y1 = ((py + 1) * height / ph).max(y0 + 1);
+        for px in 0..pw {
+            let x0 = px * width / pw;
+            let x1 = ((px + 1) * width / pw).max(x0 + 1);
+
+            let mut sum = [0.0f32; 4];
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let p = fetch(y * width + x);
+                    for (s, v) in sum.iter_mut().zip(p.iter()) {
+                        *s += v;
+                    }
+                }
+            }
+
+            let n = ((y1 - y0) * (x1 - x0)) as f32;
+            preview.push(PreviewRgba::new(
+                tonemap(sum[0] / n),
+                tonemap(sum[1] / n),
+                tonemap(sum[2] / n),
+                alpha_to_u8(sum[3] / n),
+            ));
+        }
+    }
+
+    PreviewImage::new(pw as u32, ph as u32, &preview)
+}
+
+/// Map a linear value to 8 bits using the same exposure, knee and gamma as
+/// `exrmakepreview`
+///
+fn tonemap(v: f32) -> u8 {
+    // 2^(exposure + 2.47393) with exposure 0
+    const MULTIPLIER: f32 = 5.555_776;
+    // knee compresses values over 1 rather than clipping them
+    const KNEE: f32 = 0.184_874;
+
+    let mut x = (v * MULTIPLIER).max(0.0);
+    if x > 1.0 {
+        x = 1.0 + (((x - 1.0) * KNEE + 1.0).ln() / KNEE);
+    }
+
+    (x.powf(0.4545) * 84.66).max(0.0).min(255.0) as u8
+}
+
+/// Map an alpha value in `[0, 1]` linearly to 8 bits
+///
+fn alpha_to_u8(a: f32) -> u8 {
+    (a * 255.0).max(0.0).min(255.0) as u8
+}
+
+/// Tone map a buffer of `width` by `height` half-float [`Rgba`] pixels to
+/// 8-bit RGBA, four bytes per pixel in the same order as `pixels`, e.g. for
+/// writing an LDR PNG.
+///
+/// The color channels are mapped in the same way as the pixels of a
+/// [`PreviewImage`] and alpha is scaled linearly, with every value clamped
+/// to `0..=255`.
+///
+/// # Errors
+/// * [`Error::InvalidArgument`] - if `pixels` does not contain exactly
+/// `width * height` elements
+///
+pub fn tonemap_to_rgba8(
+    pixels: &[Rgba],
+    width: usize,
+    height: usize,
+) -> Result<Vec<u8>> {
+    if pixels.len() != width * height {
+        return Err(Error::InvalidArgument(format!(
+            "pixels has {} elements but width * height is {}",
+            pixels.len(),
+            width * height
+        )));
+    }
+
+    Ok(pixels
+        .iter()
+        .flat_map(|p| {
+            [
+                tonemap(p.r.to_f32()),
+                tonemap(p.g.to_f32()),
+                tonemap(p.b.to_f32()),
+                alpha_to_u8(p.a.to_f32()),
+            ]
+        })
+        .collect())
+}
+
 #[repr(C)]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct PreviewRgba {
     r: u8,
     g: u8,
     b: u8,
     a: u8,
 }
+
+impl PreviewRgba {
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> PreviewRgba {
+        PreviewRgba { r, g, b, a }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn preview_from_rgba1() -> Result<()> {
+    use crate::tests::load_ferris;
+
+    let (pixels, width, height) = load_ferris();
+    let (width, height) = (width as usize, height as usize);
+
+    let preview_f16 = PreviewImage::from_rgba_f16(width, height, &pixels, 100)?;
+    assert_eq!(preview_f16.width().max(preview_f16.height()), 100);
+    assert_eq!(
+        preview_f16.pixels().len(),
+        (preview_f16.width() * preview_f16.height()) as usize
+    );
+
+    // the f16 and f32 paths must give identical results
+    let pixels_f32 = pixels
+        .iter()
+        .map(|p| [p.r.to_f32(), p.g.to_f32(), p.b.to_f32(), p.a.to_f32()])
+        .collect::<Vec<_>>();
+    let preview_f32 =
+        PreviewImage::from_rgba_f32(width, height, &pixels_f32, 100)?;
+    assert_eq!(preview_f16.width(), preview_f32.width());
+    assert_eq!(preview_f16.height(), preview_f32.height());
+    assert_eq!(preview_f16.pixels(), preview_f32.pixels());
+
+    // small images are not upscaled
+    let preview = PreviewImage::from_rgba_f16(width, height, &pixels, 10000)?;
+    assert_eq!(preview.width() as usize, width);
+    assert_eq!(preview.height() as usize, height);
+
+    assert!(
+        PreviewImage::from_rgba_f16(width, height, &pixels[1..], 100).is_err()
+    );
+    assert!(PreviewImage::from_rgba_f16(width, height, &pixels, 0).is_err());
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn preview_tonemap1() {
+    assert_eq!(tonemap(0.0), 0);
+    assert_eq!(tonemap(-1.0), 0);
+    // middle grey maps to roughly the same value as in exrmakepreview
+    let grey = tonemap(0.18);
+    assert!(grey > 80 && grey < 90, "grey was {}", grey);
+    assert_eq!(tonemap(1000.0), 255);
+}
+
+#[cfg(test)]
+#[test]
+fn preview_clone1() -> Result<()> {
+    use crate::core::header::Header;
+
+    let pixels = (0..12u8)
+        .map(|i| PreviewRgba::new(i, i * 2, i * 3, 255))
+        .collect::<Vec<_>>();
+    let preview = PreviewImage::new(4, 3, &pixels)?;
+
+    let mut copy = preview.clone();
+    assert!(copy == preview);
+    copy.pixels_mut()[0] = PreviewRgba::new(1, 1, 1, 1);
+    assert!(copy != preview);
+    assert_eq!(preview.pixels()[0], PreviewRgba::new(0, 0, 0, 255));
+    assert!(PreviewImage::new(3, 4, &pixels)? != preview);
+
+    // setting a clone on a second header must not alias the first
+    let mut a = Header::from_dimensions(64, 64);
+    let mut b = Header::from_dimensions(64, 64);
+    a.set_preview_image(&preview);
+    b.set_preview_image(&preview.clone());
+    b.preview_image_mut().unwrap().pixels_mut()[0] =
+        PreviewRgba::new(9, 9, 9, 9);
+    assert!(*a.preview_image()? == preview);
+    assert!(*b.preview_image()? != preview);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn tonemap_to_rgba8_1() -> Result<()> {
+    use half::f16;
+
+    let rgba = |r: f32, g: f32, b: f32, a: f32| Rgba {
+        r: f16::from_f32(r),
+        g: f16::from_f32(g),
+        b: f16::from_f32(b),
+        a: f16::from_f32(a),
+    };
+
+    // middle grey lands at the knee, HDR values clamp and negatives are
+    // black
+    let pixels = [rgba(0.0, 0.18, 10000.0, 1.0), rgba(-1.0, 0.0, 0.0, 0.5)];
+    let bytes = tonemap_to_rgba8(&pixels, 2, 1)?;
+    assert_eq!(bytes, [0, 84, 255, 255, 0, 0, 0, 127]);
+
+    assert!(tonemap_to_rgba8(&pixels, 3, 1).is_err());
+
+    Ok(())
+}
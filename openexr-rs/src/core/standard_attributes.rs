@@ -1 +1,205 @@
-// TODO
+//! Names of the standard attributes defined by OpenEXR.
+//!
+//! Use these rather than string literals when looking up attributes in a
+//! [`Header`](crate::core::header::Header) to avoid typos, e.g.
+//!
+//! ```no_run
+//! use openexr::core::header::Header;
+//! use openexr::core::standard_attributes::OWNER;
+//!
+//! let header = Header::default();
+//! let owner = header.find_typed_attribute_string(OWNER);
+//! ```
+//!
+
+// ----------------------------------------------------------------------------
+// Required attributes
+
+/// `box2i` - the rectangle describing the viewable area of the image
+pub const DISPLAY_WINDOW: &str = "displayWindow";
+/// `box2i` - the rectangle describing the portion of the image stored in the
+/// file
+pub const DATA_WINDOW: &str = "dataWindow";
+/// `float` - the ratio of a pixel's width to its height
+pub const PIXEL_ASPECT_RATIO: &str = "pixelAspectRatio";
+/// `v2f` - the center of the screen window
+pub const SCREEN_WINDOW_CENTER: &str = "screenWindowCenter";
+/// `float` - the width of the screen window
+pub const SCREEN_WINDOW_WIDTH: &str = "screenWindowWidth";
+/// `lineOrder` - the order in which scan lines are stored in the file
+pub const LINE_ORDER: &str = "lineOrder";
+/// `compression` - the compression method applied to the pixel data
+pub const COMPRESSION: &str = "compression";
+/// `chlist` - the description of the image channels
+pub const CHANNELS: &str = "channels";
+
+// ----------------------------------------------------------------------------
+// Required for tiled, deep or multi-part files
+
+/// `tiledesc` - the tile description, required for tiled images
+pub const TILES: &str = "tiles";
+/// `string` - the name of the part, required for multi-part files
+pub const NAME: &str = "name";
+/// `string` - the image type of the part, required for deep and multi-part
+/// files
+pub const TYPE: &str = "type";
+/// `int` - the version of the deep data format
+pub const VERSION: &str = "version";
+/// `int` - the number of chunks in the part, set when the file is written
+pub const CHUNK_COUNT: &str = "chunkCount";
+/// `int` - the maximum number of samples in any pixel, set when a deep file
+/// is written
+pub const MAX_SAMPLES_PER_PIXEL: &str = "maxSamplesPerPixel";
+/// `string` - the view the part belongs to
+pub const VIEW: &str = "view";
+/// `preview` - a small thumbnail of the image
+pub const PREVIEW: &str = "preview";
+
+// ----------------------------------------------------------------------------
+// Optional standard attributes
+
+/// `chromaticities` - the CIE x,y coordinates of the primaries and white
+/// point
+pub const CHROMATICITIES: &str = "chromaticities";
+/// `float` - the luminance in candelas per m² of (1, 1, 1)
+pub const WHITE_LUMINANCE: &str = "whiteLuminance";
+/// `v2f` - the CIE x,y coordinates that should be considered neutral
+pub const ADOPTED_NEUTRAL: &str = "adoptedNeutral";
+/// `string` - the name of the CTL rendering transform for the image
+pub const RENDERING_TRANSFORM: &str = "renderingTransform";
+/// `string` - the name of the CTL look modification transform for the
+/// image
+pub const LOOK_MOD_TRANSFORM: &str = "lookModTransform";
+/// `float` - the horizontal output density in pixels per inch
+pub const X_DENSITY: &str = "xDensity";
+/// `string` - the name of the owner of the image
+pub const OWNER: &str = "owner";
+/// `string` - additional image information in human-readable form
+pub const COMMENTS: &str = "comments";
+/// `string` - the software that created the image. This is a widespread
+/// convention rather than an OpenEXR standard attribute, so
+/// [`is_standard()`] is `false` for it.
+pub const SOFTWARE: &str = "software";
+/// `string` - the date the image was captured, in `YYYY:MM:DD hh:mm:ss` form
+pub const CAP_DATE: &str = "capDate";
+/// `float` - the offset of local time from UTC in seconds
+pub const UTC_OFFSET: &str = "utcOffset";
+/// `float` - the longitude where the image was captured, in degrees east
+pub const LONGITUDE: &str = "longitude";
+/// `float` - the latitude where the image was captured, in degrees north
+pub const LATITUDE: &str = "latitude";
+/// `float` - the altitude where the image was captured, in metres above sea
+/// level
+pub const ALTITUDE: &str = "altitude";
+/// `float` - the camera's focus distance in metres
+pub const FOCUS: &str = "focus";
+/// `float` - the exposure time in seconds
+pub const EXP_TIME: &str = "expTime";
+/// `float` - the camera's lens aperture in f-stops
+pub const APERTURE: &str = "aperture";
+/// `float` - the ISO speed of the film or sensor
+pub const ISO_SPEED: &str = "isoSpeed";
+/// `envmap` - the environment map layout, if the image is an environment map
+pub const ENVMAP: &str = "envmap";
+/// `keycode` - the motion picture film frame identifier
+pub const KEY_CODE: &str = "keyCode";
+/// `timecode` - the SMPTE time and control code of the frame
+pub const TIME_CODE: &str = "timeCode";
+/// `string` - how texture map images are extrapolated
+pub const WRAPMODES: &str = "wrapmodes";
+/// `rational` - the playback frame rate
+pub const FRAMES_PER_SECOND: &str = "framesPerSecond";
+/// `stringvector` - the views in a multi-view image
+pub const MULTI_VIEW: &str = "multiView";
+/// `m44f` - the transformation from world space to camera space
+pub const WORLD_TO_CAMERA: &str = "worldToCamera";
+/// `m44f` - the transformation from world space to normalized device
+/// coordinates
+pub const WORLD_TO_NDC: &str = "worldToNDC";
+/// `deepImageState` - the state of the samples in a deep image
+pub const DEEP_IMAGE_STATE: &str = "deepImageState";
+/// `box2i` - the data window of the image before it was cropped
+pub const ORIGINAL_DATA_WINDOW: &str = "originalDataWindow";
+/// `float` - the quality level used for DWAA and DWAB compression
+pub const DWA_COMPRESSION_LEVEL: &str = "dwaCompressionLevel";
+
+/// The names of the attributes that every header must contain
+///
+pub const REQUIRED: &[&str] = &[
+    DISPLAY_WINDOW,
+    DATA_WINDOW,
+    PIXEL_ASPECT_RATIO,
+    SCREEN_WINDOW_CENTER,
+    SCREEN_WINDOW_WIDTH,
+    LINE_ORDER,
+    COMPRESSION,
+    CHANNELS,
+];
+
+/// Get the type name that the standard attribute `name` must have, e.g.
+/// `"m44f"` for [`WORLD_TO_CAMERA`].
+///
+/// # Returns
+/// * `Some(&str)` - if `name` is a standard attribute
+/// * `None` - otherwise
+///
+pub fn type_name_of(name: &str) -> Option<&'static str> {
+    Some(match name {
+        DISPLAY_WINDOW | DATA_WINDOW | ORIGINAL_DATA_WINDOW => "box2i",
+        PIXEL_ASPECT_RATIO
+        | SCREEN_WINDOW_WIDTH
+        | WHITE_LUMINANCE
+        | X_DENSITY
+        | UTC_OFFSET
+        | LONGITUDE
+        | LATITUDE
+        | ALTITUDE
+        | FOCUS
+        | EXP_TIME
+        | APERTURE
+        | ISO_SPEED
+        | DWA_COMPRESSION_LEVEL => "float",
+        SCREEN_WINDOW_CENTER | ADOPTED_NEUTRAL => "v2f",
+        LINE_ORDER => "lineOrder",
+        COMPRESSION => "compression",
+        CHANNELS => "chlist",
+        TILES => "tiledesc",
+        NAME | TYPE | VIEW | RENDERING_TRANSFORM | LOOK_MOD_TRANSFORM
+        | OWNER | COMMENTS | CAP_DATE | WRAPMODES => "string",
+        VERSION | CHUNK_COUNT | MAX_SAMPLES_PER_PIXEL => "int",
+        PREVIEW => "preview",
+        CHROMATICITIES => "chromaticities",
+        ENVMAP => "envmap",
+        KEY_CODE => "keycode",
+        TIME_CODE => "timecode",
+        FRAMES_PER_SECOND => "rational",
+        MULTI_VIEW => "stringvector",
+        WORLD_TO_CAMERA | WORLD_TO_NDC => "m44f",
+        DEEP_IMAGE_STATE => "deepImageState",
+        _ => return None,
+    })
+}
+
+/// Is `name` one of the standard attributes defined by OpenEXR?
+///
+pub fn is_standard(name: &str) -> bool {
+    type_name_of(name).is_some()
+}
+
+/// Is `name` one of the attributes that every header must contain?
+///
+pub fn is_required(name: &str) -> bool {
+    REQUIRED.contains(&name)
+}
+
+#[cfg(test)]
+#[test]
+fn standard_attributes1() {
+    assert!(is_standard(WORLD_TO_CAMERA));
+    assert!(!is_standard("myStudioAttribute"));
+    assert!(!is_standard(SOFTWARE));
+    assert!(is_required(CHANNELS));
+    assert!(!is_required(OWNER));
+    assert_eq!(type_name_of(FRAMES_PER_SECOND), Some("rational"));
+    assert!(REQUIRED.iter().all(|name| is_standard(name)));
+}
@@ -2,7 +2,13 @@ use openexr_sys as sys;
 
 use crate::core::{LevelMode, LevelRoundingMode};
 
-#[derive(Debug, Copy, Clone)]
+/// Describes the size of the tiles in a tiled image, and how its resolution
+/// levels are laid out.
+///
+/// Two descriptions are equal if their tile sizes, level modes and level
+/// rounding modes are all equal.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct TileDescription {
     x_size: u32,
     y_size: u32,
@@ -24,6 +30,48 @@ impl TileDescription {
             rounding_mode,
         }
     }
+
+    /// The width of a tile in pixels
+    pub fn x_size(&self) -> u32 {
+        self.x_size
+    }
+
+    /// The height of a tile in pixels
+    pub fn y_size(&self) -> u32 {
+        self.y_size
+    }
+
+    /// How the resolution levels of the image are laid out
+    pub fn mode(&self) -> LevelMode {
+        self.mode
+    }
+
+    /// Whether the sizes of lower resolution levels are rounded up or down
+    pub fn rounding_mode(&self) -> LevelRoundingMode {
+        self.rounding_mode
+    }
+}
+
+impl std::hash::Hash for TileDescription {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.x_size.hash(state);
+        self.y_size.hash(state);
+        (self.mode as i32).hash(state);
+        (self.rounding_mode as i32).hash(state);
+    }
+}
+
+impl Default for TileDescription {
+    /// 32x32 tiles with a single level, the same as OpenEXR's default
+    ///
+    fn default() -> Self {
+        TileDescription::new(
+            32,
+            32,
+            LevelMode::OneLevel,
+            LevelRoundingMode::RoundDown,
+        )
+    }
 }
 
 impl From<sys::Imf_TileDescription_t> for TileDescription {
@@ -47,3 +95,44 @@ impl From<TileDescription> for sys::Imf_TileDescription_t {
         }
     }
 }
+
+#[cfg(test)]
+#[test]
+fn tile_description_eq1() {
+    assert_eq!(TileDescription::default(), TileDescription::default());
+    assert_eq!(
+        TileDescription::default(),
+        TileDescription::new(
+            32,
+            32,
+            LevelMode::OneLevel,
+            LevelRoundingMode::RoundDown
+        )
+    );
+
+    let mip = TileDescription::new(
+        32,
+        32,
+        LevelMode::MipmapLevels,
+        LevelRoundingMode::RoundDown,
+    );
+    assert_ne!(TileDescription::default(), mip);
+    assert_ne!(
+        mip,
+        TileDescription::new(
+            32,
+            32,
+            LevelMode::MipmapLevels,
+            LevelRoundingMode::RoundUp
+        )
+    );
+    assert_ne!(
+        TileDescription::default(),
+        TileDescription::new(
+            64,
+            32,
+            LevelMode::OneLevel,
+            LevelRoundingMode::RoundDown
+        )
+    );
+}
@@ -101,6 +101,7 @@ impl TimeCodePacking {
 /// | 24 - 27 | binary group 7 |
 /// | 28 - 31 | binary group 8 |
 ///
+#[repr(transparent)]
 pub struct TimeCode {
     inner: sys::Imf_TimeCode_t,
 }
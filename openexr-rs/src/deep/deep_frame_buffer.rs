@@ -231,6 +231,78 @@ impl DeepFrameBuffer {
 
         Ok(())
     }
+
+    /// Check that the samples of every pixel are tidy, i.e. sorted
+    /// front-to-back and non-overlapping, as required for files whose
+    /// `deepImageState` is [`DeepImageState::Tidy`](crate::deep::DeepImageState::Tidy).
+    ///
+    /// The depths are taken from the `"Z"` and, if present, `"ZBack"`
+    /// [`DeepFrame`]s inserted with [`insert_deep_frame()`](DeepFrameBuffer::insert_deep_frame).
+    /// `counts` holds the number of samples of each pixel of the `"Z"` frame's
+    /// data window, in scanline order.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if there is no `"Z"` frame, the length of
+    /// `counts` or the data window of `"ZBack"` does not match, or a pixel's
+    /// samples are not tidy
+    ///
+    /// # Safety
+    /// The sample pointers of the `"Z"` and `"ZBack"` frames are read
+    /// directly, so for every pixel `i` they must point to initialized
+    /// storage for at least `counts[i]` samples, e.g. after the sample counts
+    /// have been read and the storage allocated and filled by
+    /// `read_pixels()`.
+    ///
+    pub unsafe fn verify_tidy(&self, counts: &[u32]) -> Result<()> {
+        let frames = self.frames.as_deref().unwrap_or_default();
+        let find = |name: &str| frames.iter().find(|f| f.channel_name == name);
+
+        let z = find("Z").ok_or_else(|| {
+            Error::InvalidArgument("no \"Z\" deep frame".into())
+        })?;
+        let z_back = find("ZBack");
+
+        if counts.len() != z.len {
+            return Err(Error::InvalidArgument(format!(
+                "expected {} sample counts, got {}",
+                z.len,
+                counts.len()
+            )));
+        }
+        if let Some(zb) = z_back {
+            if zb.data_window != z.data_window {
+                return Err(Error::InvalidArgument(
+                    "\"Z\" and \"ZBack\" data windows differ".into(),
+                ));
+            }
+        }
+
+        let w = (z.data_window[2] - z.data_window[0] + 1) as usize;
+        for (i, &count) in counts.iter().enumerate() {
+            let depth = |s: usize| {
+                let front = z.sample_as_f32(i, s);
+                let back = z_back.map_or(front, |zb| zb.sample_as_f32(i, s));
+                (front, back)
+            };
+
+            let mut prev: Option<f32> = None;
+            for s in 0..count as usize {
+                let (front, back) = depth(s);
+                let tidy = front <= back && prev.map_or(true, |pb| pb <= front);
+                if !tidy {
+                    return Err(Error::InvalidArgument(format!(
+                        "samples of pixel ({}, {}) are not tidy at sample {}",
+                        z.data_window[0] + (i % w) as i32,
+                        z.data_window[1] + (i / w) as i32,
+                        s
+                    )));
+                }
+                prev = Some(back);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Drop for DeepFrameBuffer {
@@ -557,6 +629,22 @@ impl DeepFrame {
         let offset = (y * w + x) as isize;
         *self.ptr.offset(offset) = ptr;
     }
+
+    /// Get sample `sample` of the pixel at `index` in the data window as an
+    /// `f32`
+    ///
+    /// # Safety
+    /// The storage for the pixel must hold at least `sample + 1` samples
+    ///
+    unsafe fn sample_as_f32(&self, index: usize, sample: usize) -> f32 {
+        let ptr = (*self.ptr.add(index)).add(sample * self.stride);
+        match self.channel_type {
+            PixelType::Half => (*(ptr as *const half::f16)).to_f32(),
+            PixelType::Float => *(ptr as *const f32),
+            PixelType::Uint => *(ptr as *const u32) as f32,
+            _ => f32::NAN,
+        }
+    }
 }
 
 impl Drop for DeepFrame {
@@ -573,3 +661,42 @@ impl Drop for DeepFrame {
         }
     }
 }
+
+#[cfg(test)]
+#[test]
+fn deep_frame_buffer_verify_tidy1() -> Result<()> {
+    let data_window = [0, 0, 1, 0];
+    let mut z0 = vec![1.0f32, 2.0, 4.0];
+    let mut z1 = vec![3.0f32, 4.0];
+    let mut zb0 = vec![2.0f32, 3.0, 5.0];
+    let mut zb1 = vec![3.0f32, 4.5];
+
+    let frame = |name, a: &mut Vec<f32>, b: &mut Vec<f32>| {
+        let f = DeepFrame::new::<f32, _>(name, data_window)?;
+        unsafe {
+            *f.ptr = a.as_mut_ptr() as *mut u8;
+            *f.ptr.add(1) = b.as_mut_ptr() as *mut u8;
+        }
+        Ok::<_, Error>(f)
+    };
+
+    let mut frame_buffer = DeepFrameBuffer::new();
+    assert!(unsafe { frame_buffer.verify_tidy(&[3, 2]) }.is_err());
+
+    frame_buffer.insert_deep_frame(frame("Z", &mut z0, &mut z1)?)?;
+    frame_buffer.insert_deep_frame(frame("ZBack", &mut zb0, &mut zb1)?)?;
+    unsafe { frame_buffer.verify_tidy(&[3, 2]) }?;
+    assert!(unsafe { frame_buffer.verify_tidy(&[3]) }.is_err());
+
+    // the volume sample [2, 3] now overlaps [1, 2.5]
+    zb0[0] = 2.5;
+    assert!(unsafe { frame_buffer.verify_tidy(&[3, 2]) }.is_err());
+    zb0[0] = 2.0;
+
+    // samples out of order
+    z1.swap(0, 1);
+    zb1.swap(0, 1);
+    assert!(unsafe { frame_buffer.verify_tidy(&[3, 2]) }.is_err());
+
+    Ok(())
+}
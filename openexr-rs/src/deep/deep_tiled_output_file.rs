@@ -651,7 +651,7 @@ fn test_write_deep_tiled1() -> Result<()> {
         64,
         LevelMode::OneLevel,
         LevelRoundingMode::RoundUp,
-    ));
+    ))?;
     header.channels_mut().insert("Z", &CHANNEL_FLOAT);
     header.channels_mut().insert("A", &CHANNEL_HALF);
 
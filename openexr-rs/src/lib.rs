@@ -178,6 +178,8 @@ pub mod flat;
 pub mod multi_part;
 pub mod prelude;
 pub mod rgba;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod tiled;
 pub mod util;
 
@@ -3,7 +3,11 @@ use openexr_sys as sys;
 use std::ffi::CString;
 use std::path::Path;
 
-use crate::core::{error::Error, header::HeaderRef, version::Version};
+use crate::core::{
+    error::Error,
+    header::{Header, HeaderRef},
+    version::Version,
+};
 
 type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -90,6 +94,23 @@ impl MultiPartInputFile {
         }
     }
 
+    /// Read owned copies of the headers of every part of the file at
+    /// `filename`, e.g. to list the parts it contains.
+    ///
+    /// The file is closed before returning, so the headers are independent
+    /// of it and of each other.
+    ///
+    /// # Errors
+    /// * [`Error::Base`] - if the file cannot be opened or its headers cannot
+    /// be read
+    ///
+    pub fn read_headers<P: AsRef<Path>>(filename: P) -> Result<Vec<Header>> {
+        let file = MultiPartInputFile::new(filename, 1, false)?;
+        (0..file.parts())
+            .map(|n| file.header(n).map(|h| Header::clone(&h)))
+            .collect()
+    }
+
     /// Get the file format version
     ///
     pub fn version(&self) -> Version {
@@ -163,3 +184,28 @@ fn read_multipartinputfile1() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+#[test]
+fn read_multipartinputfile_read_headers1() -> Result<()> {
+    use std::path::PathBuf;
+
+    let path = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR not set"),
+    )
+    .join("images")
+    .join("ferris-multipart.exr");
+
+    let mut headers = MultiPartInputFile::read_headers(&path)?;
+    assert_eq!(headers.len(), 2);
+
+    // drop the first part's header before using the second
+    let right = headers.pop().unwrap();
+    drop(headers);
+    assert_eq!(right.name().unwrap(), "right");
+
+    assert!(MultiPartInputFile::read_headers("does_not_exist.exr").is_err());
+
+    Ok(())
+}
@@ -5,7 +5,10 @@ use std::path::Path;
 
 use crate::core::{
     error::Error,
-    header::{HeaderRef, HeaderSlice},
+    frame_buffer::FrameBuffer,
+    header::{Header, HeaderRef, HeaderSlice},
+    output_part::OutputPart,
+    LineOrder,
 };
 
 type Result<T, E = Error> = std::result::Result<T, E>;
@@ -95,6 +98,73 @@ impl MultiPartOutputFile {
         }
         Ok(HeaderRef::new(ptr))
     }
+
+    /// Write every remaining scan line of every part in one go, taking the
+    /// pixels of each part from the [`FrameBuffer`] paired with its index in
+    /// `buffers`.
+    ///
+    /// Each part writes as many scan lines as it has left, given its data
+    /// window, line order and what has already been written to it, so parts
+    /// may have different heights.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - If any part does not have exactly one
+    /// buffer in `buffers`, or if a frame buffer does not match its part.
+    /// * [`Error::OutOfRange`] - If a part index in `buffers` does not index a
+    /// part in the file.
+    /// * [`Error::Io`] - If an I/O error occurs while writing the image data
+    /// * [`Error::Base`] - If any other error occurs, e.g. a part is not a
+    /// flat scanline part
+    ///
+    /// # Safety
+    /// This method is unsafe for the same reasons as
+    /// [`OutputPart::write_pixels`]: the pixels are read from the pointers in
+    /// the [`Slice`](crate::core::frame_buffer::Slice)s of each
+    /// [`FrameBuffer`], which must point to storage covering the part's data
+    /// window.
+    ///
+    pub unsafe fn write_all(
+        &mut self,
+        buffers: &[(usize, &FrameBuffer)],
+    ) -> Result<()> {
+        let parts = self.parts() as usize;
+        if buffers.iter().any(|(n, _)| *n >= parts) {
+            return Err(Error::OutOfRange);
+        }
+
+        for part in 0..parts {
+            let count = buffers.iter().filter(|(n, _)| *n == part).count();
+            if count != 1 {
+                return Err(Error::InvalidArgument(format!(
+                    "Part {} has {} frame buffers, expected 1",
+                    part, count
+                )));
+            }
+        }
+
+        for (part, frame_buffer) in buffers {
+            let (dw, line_order) = {
+                let header = self.header(*part as i32)?;
+                (*header.data_window::<[i32; 4]>(), header.line_order())
+            };
+
+            let mut output = OutputPart::new(self, *part as i32)?;
+            output.set_frame_buffer(frame_buffer)?;
+
+            let current = output.current_scan_line();
+            let remaining = match line_order {
+                LineOrder::DecreasingY => current - dw[1] + 1,
+                _ => dw[3] - current + 1,
+            };
+            if remaining > 0 {
+                output
+                    .write_pixels(remaining)
+                    .map_err(|e| e.with_context(&format!("part {}", part)))?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Drop for MultiPartOutputFile {
@@ -105,6 +175,63 @@ impl Drop for MultiPartOutputFile {
     }
 }
 
+/// Check that `headers` can be written together as the parts of a multi-part
+/// file.
+///
+/// Every part must have a name that is unique among the parts and an image
+/// type, and all parts must share the same `displayWindow` and
+/// `pixelAspectRatio`. Checking this up front gives a clearer error than
+/// [`MultiPartOutputFile::new()`], which only reports the first problem it
+/// finds in its own terms.
+///
+/// # Errors
+/// * [`Error::InvalidHeader`] - naming the first inconsistent part and
+/// attribute
+///
+pub fn validate_multipart_consistency(headers: &[Header]) -> Result<()> {
+    let invalid = |msg: String| Err(Error::InvalidHeader(vec![msg]));
+
+    let mut names = Vec::with_capacity(headers.len());
+    for (i, header) in headers.iter().enumerate() {
+        let name = match header.name() {
+            Ok(name) if header.has_name() => name,
+            _ => return invalid(format!("part {} has no name", i)),
+        };
+        if let Some(j) = names.iter().position(|n| *n == name) {
+            return invalid(format!(
+                "parts {} and {} are both called \"{}\"",
+                j, i, name
+            ));
+        }
+        if !header.has_image_type() {
+            return invalid(format!("part {} (\"{}\") has no type", i, name));
+        }
+
+        let first = &headers[0];
+        let window = header.display_window::<[i32; 4]>();
+        let first_window = first.display_window::<[i32; 4]>();
+        if window != first_window {
+            return invalid(format!(
+                "part {} (\"{}\") has displayWindow {:?} but part 0 has {:?}",
+                i, name, window, first_window
+            ));
+        }
+
+        let par = header.pixel_aspect_ratio();
+        let first_par = first.pixel_aspect_ratio();
+        if par != first_par {
+            return invalid(format!(
+                "part {} (\"{}\") has pixelAspectRatio {} but part 0 has {}",
+                i, name, par, first_par
+            ));
+        }
+
+        names.push(name);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 #[test]
 fn write_multipartoutputfile1() {
@@ -143,10 +270,10 @@ fn write_multipartoutputfile1() {
         header.set_dimensions(width, height);
 
         if i == 0 {
-            header.set_name("left");
+            header.set_name("left").unwrap();
             i += 1;
         } else {
-            header.set_name("right");
+            header.set_name("right").unwrap();
         }
     }
 
@@ -293,3 +420,142 @@ fn write_multipartoutputfile1() {
         part_right.write_pixels(height).unwrap();
     }
 }
+
+#[cfg(test)]
+#[test]
+fn write_multipartoutputfile_write_all1() -> Result<()> {
+    use crate::{
+        core::{
+            channel_list::Channel,
+            header::{Header, ImageType},
+            input_part::InputPart,
+            PixelType,
+        },
+        multi_part::multi_part_input_file::MultiPartInputFile,
+    };
+    use half::f16;
+
+    let channel = Channel {
+        type_: PixelType::Half.into(),
+        x_sampling: 1,
+        y_sampling: 1,
+        p_linear: true,
+    };
+
+    // parts of different heights and line orders
+    let sizes = [(16, 8), (16, 5)];
+    let mut headers = Header::new_array(2);
+    for (i, mut header) in headers.iter_mut().enumerate() {
+        header.channels_mut().insert("Y", &channel);
+        header.set_image_type(ImageType::Scanline);
+        header.set_dimensions(sizes[i].0, sizes[i].1);
+        header.set_name(if i == 0 { "first" } else { "second" })?;
+        if i == 1 {
+            header.set_line_order(LineOrder::DecreasingY);
+        }
+    }
+
+    let mut data = sizes
+        .iter()
+        .enumerate()
+        .map(|(i, (w, h))| {
+            (0..w * h)
+                .map(|v| f16::from_f32((v + i as i32 * 1000) as f32))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut frame_buffers = Vec::new();
+    for (d, (w, h)) in data.iter_mut().zip(sizes.iter()) {
+        let mut frame_buffer = FrameBuffer::new();
+        unsafe {
+            frame_buffer.insert_channel_with_fill(
+                "Y",
+                d,
+                [0, 0, w - 1, h - 1],
+                0.0,
+            )
+        }?;
+        frame_buffers.push(frame_buffer);
+    }
+
+    let mut file = MultiPartOutputFile::new(
+        "write_multipartoutputfile_write_all1.exr",
+        &headers,
+        true,
+        1,
+    )?;
+
+    unsafe {
+        // every part needs a buffer
+        assert!(file.write_all(&[(0, &frame_buffers[0])]).is_err());
+        assert!(matches!(
+            file.write_all(&[(0, &frame_buffers[0]), (2, &frame_buffers[1])]),
+            Err(Error::OutOfRange)
+        ));
+
+        file.write_all(&[(1, &frame_buffers[1]), (0, &frame_buffers[0])])?;
+    }
+    std::mem::drop(file);
+
+    let file = MultiPartInputFile::new(
+        "write_multipartoutputfile_write_all1.exr",
+        1,
+        false,
+    )?;
+    for (i, (w, h)) in sizes.iter().enumerate() {
+        let mut read = vec![f16::ZERO; (w * h) as usize];
+        let mut frame_buffer = FrameBuffer::new();
+        unsafe {
+            frame_buffer.insert_channel_with_fill(
+                "Y",
+                &mut read,
+                [0, 0, w - 1, h - 1],
+                0.0,
+            )
+        }?;
+
+        let mut part = InputPart::new(&file, i as i32)?;
+        part.set_frame_buffer(&frame_buffer)?;
+        part.read_pixels(0, h - 1)?;
+        assert!(read == data[i]);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn validate_multipart_consistency1() -> Result<()> {
+    use crate::core::header::ImageType;
+
+    let part = |name: &str| -> Result<Header> {
+        let mut header = Header::from_dimensions(16, 8);
+        header.set_name(name)?;
+        header.set_image_type(ImageType::Scanline);
+        Ok(header)
+    };
+
+    let mut headers = vec![part("left")?, part("right")?];
+    validate_multipart_consistency(&headers)?;
+    validate_multipart_consistency(&[])?;
+
+    let message =
+        |headers: &[Header]| match validate_multipart_consistency(headers) {
+            Err(Error::InvalidHeader(msgs)) => msgs.join("; "),
+            r => panic!("expected InvalidHeader, got {:?}", r),
+        };
+
+    assert!(message(&[part("left")?, part("left")?]).contains("\"left\""));
+    assert!(message(&[part("left")?, Header::from_dimensions(16, 8)])
+        .contains("part 1 has no name"));
+
+    headers[1].set_pixel_aspect_ratio(2.0)?;
+    assert!(message(&headers).contains("pixelAspectRatio"));
+
+    headers[1] = part("right")?;
+    *headers[1].display_window_mut::<[i32; 4]>() = [0, 0, 31, 7];
+    assert!(message(&headers).contains("displayWindow"));
+
+    Ok(())
+}
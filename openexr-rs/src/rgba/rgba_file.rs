@@ -7,10 +7,10 @@ use crate::{
         Compression, LineOrder,
     },
     rgba::rgba::{Rgba, RgbaChannels},
+    util::path_to_cstring,
 };
 use imath_traits::Vec2;
 use openexr_sys as sys;
-use std::ffi::CString;
 use std::path::Path;
 
 type Result<T, E = Error> = std::result::Result<T, E>;
@@ -50,6 +50,7 @@ impl RgbaOutputFile {
     ///
     /// ## Errors
     /// * [`Error::Base`] - If an error occurs
+    /// * [`Error::InvalidArgument`] - If `filename` contains a NUL byte
     ///
     pub fn new<P: AsRef<Path>>(
         filename: P,
@@ -57,13 +58,7 @@ impl RgbaOutputFile {
         channels: RgbaChannels,
         num_threads: i32,
     ) -> Result<RgbaOutputFile> {
-        let c_filename = CString::new(
-            filename
-                .as_ref()
-                .to_str()
-                .expect("Invalid bytes in filename"),
-        )
-        .expect("Internal null bytes in filename");
+        let c_filename = path_to_cstring(filename.as_ref())?;
 
         let mut _inner = std::ptr::null_mut();
         unsafe {
@@ -99,6 +94,7 @@ impl RgbaOutputFile {
     ///
     /// ## Errors
     /// * [`Error::Base`] - If an error occurs
+    /// * [`Error::InvalidArgument`] - If `filename` contains a NUL byte
     ///
     #[allow(clippy::too_many_arguments)]
     pub fn with_dimensions<P: AsRef<Path>, V>(
@@ -116,13 +112,7 @@ impl RgbaOutputFile {
     where
         V: Vec2<f32>,
     {
-        let c_filename = CString::new(
-            filename
-                .as_ref()
-                .to_str()
-                .expect("Invalid bytes in filename"),
-        )
-        .expect("Internal null bytes in filename");
+        let c_filename = path_to_cstring(filename.as_ref())?;
 
         let s = sys::Imath_V2f_t {
             x: screen_window_center.as_slice()[0],
@@ -347,6 +337,7 @@ impl RgbaInputFile {
     ///
     /// ## Errors
     /// * [`Error::Base`] - If an error occurs
+    /// * [`Error::InvalidArgument`] - If `filename` contains a NUL byte
     ///
     pub fn new<P: AsRef<Path>>(
         filename: P,
@@ -354,13 +345,7 @@ impl RgbaInputFile {
     ) -> Result<RgbaInputFile> {
         let mut inner = std::ptr::null_mut();
 
-        let c_filename = CString::new(
-            filename
-                .as_ref()
-                .to_str()
-                .expect("Invalid bytes in filename"),
-        )
-        .expect("Internal null bytes in filename");
+        let c_filename = path_to_cstring(filename.as_ref())?;
 
         unsafe {
             sys::Imf_RgbaInputFile_ctor(
@@ -0,0 +1,104 @@
+//! Helpers for measuring how OpenEXR treats your data.
+//!
+//! These are only available with the `testing` feature enabled.
+//!
+
+use crate::{
+    core::{error::Error, header::Header, Compression},
+    rgba::{
+        rgba::{Rgba, RgbaChannels},
+        rgba_file::{RgbaInputFile, RgbaOutputFile},
+    },
+};
+
+use imath_traits::Zero;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Write `pixels` as a `width` by `height` RGBA image using `compression`,
+/// then read them back.
+///
+/// Comparing the result against `pixels` shows how much error a lossy
+/// compression method such as [`Compression::Dwaa`] introduces for a given
+/// image. Lossless methods return `pixels` unchanged.
+///
+/// The bindings do not yet expose in-memory streams, so the image is written
+/// to a uniquely-named file in [`std::env::temp_dir()`] which is removed
+/// before returning.
+///
+/// ## Errors
+/// * [`Error::InvalidArgument`] - if `pixels` does not contain exactly
+/// `width * height` elements
+/// * [`Error::Base`] - if writing or reading the image fails
+///
+pub fn roundtrip_rgba(
+    pixels: &[Rgba],
+    width: i32,
+    height: i32,
+    compression: Compression,
+) -> Result<Vec<Rgba>> {
+    if width < 1 || height < 1 || pixels.len() != (width * height) as usize {
+        return Err(Error::InvalidArgument(format!(
+            "pixels has {} elements but the image is {}x{}",
+            pixels.len(),
+            width,
+            height
+        )));
+    }
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "openexr-roundtrip-{}-{}.exr",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = write_and_read(&path, pixels, width, height, compression);
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+fn write_and_read(
+    path: &std::path::Path,
+    pixels: &[Rgba],
+    width: i32,
+    height: i32,
+    compression: Compression,
+) -> Result<Vec<Rgba>> {
+    let mut header = Header::from_dimensions(width, height);
+    header.set_compression(compression);
+
+    {
+        let mut file =
+            RgbaOutputFile::new(path, &header, RgbaChannels::WriteRgba, 1)?;
+        file.set_frame_buffer(pixels, 1, width as usize)?;
+        file.write_pixels(height)?;
+    }
+
+    let mut file = RgbaInputFile::new(path, 1)?;
+    let mut result = vec![Rgba::zero(); pixels.len()];
+    file.set_frame_buffer(&mut result, 1, width as usize)?;
+    file.read_pixels(0, height - 1)?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+#[test]
+fn roundtrip_rgba1() -> Result<()> {
+    let (pixels, width, height) = crate::tests::load_ferris();
+
+    let lossless = roundtrip_rgba(&pixels, width, height, Compression::Zip)?;
+    assert_eq!(lossless, pixels);
+
+    let lossy = roundtrip_rgba(&pixels, width, height, Compression::Dwaa)?;
+    assert_eq!(lossy.len(), pixels.len());
+
+    assert!(
+        roundtrip_rgba(&pixels[1..], width, height, Compression::Zip).is_err()
+    );
+
+    Ok(())
+}
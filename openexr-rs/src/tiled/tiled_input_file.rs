@@ -1,14 +1,16 @@
 use crate::core::{
     error::Error,
-    frame_buffer::{FrameBuffer, FrameBufferRef},
+    frame_buffer::{check_channel_type, FrameBuffer, FrameBufferRef, Pixel},
     header::HeaderRef,
     version::Version,
     LevelMode, LevelRoundingMode,
 };
 
+use crate::util::{cstr_to_path, path_to_cstring};
+
 use imath_traits::Bound2;
 use openexr_sys as sys;
-use std::ffi::{CStr, CString};
+use std::ffi::CStr;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 
@@ -42,22 +44,20 @@ impl TiledInputFile {
     /// Destroying TiledInputFile objects constructed with this constructor
     /// automatically closes the corresponding files.
     ///
+    /// On Unix the path is passed to OpenEXR as raw bytes, so it does not
+    /// need to be valid UTF-8.
+    ///
     /// # Errors
     ///
-    /// This will return an error if the file is not tiled.
+    /// This will return an error if the file is not tiled, or
+    /// [`Error::InvalidArgument`] if `file_name` contains a NUL byte.
     ///
     pub fn new<P: AsRef<Path>>(
         file_name: P,
         num_threads: i32,
     ) -> Result<Self, Error> {
         let mut ptr = std::ptr::null_mut();
-        let c_file_name = CString::new(
-            file_name
-                .as_ref()
-                .to_str()
-                .expect("Invalid bytes in filename"),
-        )
-        .expect("Internal null bytes in filename");
+        let c_file_name = path_to_cstring(file_name.as_ref())?;
 
         unsafe {
             sys::Imf_TiledInputFile_ctor(
@@ -95,11 +95,7 @@ impl TiledInputFile {
                 );
             }
 
-            let file_name = CStr::from_ptr(ptr);
-
-            PathBuf::from(
-                file_name.to_str().expect("Invalid bytes in filename"),
-            )
+            cstr_to_path(CStr::from_ptr(ptr))
         }
     }
 
@@ -195,6 +191,59 @@ impl TiledInputFile {
         v
     }
 
+    /// Check if the tile with tile coordinates `(dx, dy)` and level number
+    /// `(lx, ly)` is present in the file.
+    ///
+    /// This can be used to find out which tiles are missing from a partially
+    /// written file. Tile coordinates or levels that are out of range for the
+    /// file return `false` rather than an error.
+    ///
+    /// OpenEXR only reports a missing tile when it is read, so this reopens
+    /// the file and reads the tile into a scratch buffer. The frame buffer of
+    /// this `TiledInputFile` is left untouched.
+    ///
+    pub fn tile_exists(&self, dx: i32, dy: i32, lx: i32, ly: i32) -> bool {
+        if !self.is_valid_level(lx, ly) {
+            return false;
+        }
+
+        match (self.num_x_tiles(lx), self.num_y_tiles(ly)) {
+            (Ok(nx), Ok(ny)) if dx >= 0 && dx < nx && dy >= 0 && dy < ny => (),
+            _ => return false,
+        }
+
+        let dw = match self.data_window_for_tile::<[i32; 4]>(dx, dy, lx, ly) {
+            Ok(dw) => dw,
+            Err(_) => return false,
+        };
+        let channel = match self.header().channels().iter().next() {
+            Some((name, _)) => name.to_string(),
+            None => return false,
+        };
+
+        let mut scratch = vec![
+            0.0f32;
+            (dw[2] - dw[0] + 1) as usize
+                * (dw[3] - dw[1] + 1) as usize
+        ];
+        let result = (|| -> Result<()> {
+            let mut file = TiledInputFile::new(self.file_name(), 1)?;
+            let mut frame_buffer = FrameBuffer::new();
+            unsafe {
+                frame_buffer.insert_channel_with_fill(
+                    &channel,
+                    &mut scratch,
+                    dw,
+                    0.0,
+                )
+            }?;
+            file.set_frame_buffer(&frame_buffer)?;
+            file.read_tile(dx, dy, lx, ly)
+        })();
+
+        result.is_ok()
+    }
+
     /// Get the tiles' x dimension
     ///
     pub fn tile_x_size(&self) -> u32 {
@@ -521,6 +570,115 @@ impl TiledInputFile {
         }
         Ok(())
     }
+
+    /// Read `channel` from every level of a one-level or mipmapped file, from
+    /// the highest resolution to the lowest.
+    ///
+    /// Each level is returned as `(level_width, level_height, pixels)`, with
+    /// the pixels stored one row after another. If the file does not contain
+    /// `channel`, the pixels are filled with zeroes.
+    ///
+    /// Ripmapped files have a 2D grid of levels, so use
+    /// [`read_all_ripmap_levels()`](TiledInputFile::read_all_ripmap_levels)
+    /// for those instead.
+    ///
+    /// This replaces the current frame buffer with an empty one, so
+    /// [`set_frame_buffer()`](TiledInputFile::set_frame_buffer) must be
+    /// called again before any further calls to
+    /// [`read_tile()`](TiledInputFile::read_tile).
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if the file is ripmapped, or if `T` has
+    /// more than one channel or cannot hold the pixels of `channel` without
+    /// losing data
+    /// * [`Error::Io`] - if there is an error reading data from the file
+    /// * [`Error::Base`] - if any other error occurs
+    ///
+    pub fn read_all_levels<T: Pixel>(
+        &mut self,
+        channel: &str,
+    ) -> Result<Vec<(i32, i32, Vec<T>)>> {
+        if self.level_mode() == LevelMode::RipmapLevels {
+            return Err(Error::InvalidArgument(
+                "read_all_levels() cannot read ripmapped files, use \
+                read_all_ripmap_levels() instead"
+                    .to_string(),
+            ));
+        }
+
+        (0..self.num_levels()?)
+            .map(|l| self.read_level(channel, l, l))
+            .collect()
+    }
+
+    /// Read `channel` from every level of the file, whatever its level mode.
+    ///
+    /// Each level is returned as `(lx, ly, level_width, level_height,
+    /// pixels)`, ordered by `ly` then `lx`, with the pixels stored one row
+    /// after another. For mipmapped files only levels with `lx == ly` exist.
+    /// If the file does not contain `channel`, the pixels are filled with
+    /// zeroes.
+    ///
+    /// As with [`read_all_levels()`](TiledInputFile::read_all_levels), this
+    /// replaces the current frame buffer with an empty one.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidArgument`] - if `T` has more than one channel or
+    /// cannot hold the pixels of `channel` without losing data
+    /// * [`Error::Io`] - if there is an error reading data from the file
+    /// * [`Error::Base`] - if any other error occurs
+    ///
+    pub fn read_all_ripmap_levels<T: Pixel>(
+        &mut self,
+        channel: &str,
+    ) -> Result<Vec<(i32, i32, i32, i32, Vec<T>)>> {
+        let mut levels = Vec::new();
+        for ly in 0..self.num_y_levels() {
+            for lx in 0..self.num_x_levels() {
+                if self.is_valid_level(lx, ly) {
+                    let (w, h, pixels) = self.read_level(channel, lx, ly)?;
+                    levels.push((lx, ly, w, h, pixels));
+                }
+            }
+        }
+
+        Ok(levels)
+    }
+
+    /// Read all the tiles of level `(lx, ly)` of `channel` into a packed
+    /// buffer
+    ///
+    fn read_level<T: Pixel>(
+        &mut self,
+        channel: &str,
+        lx: i32,
+        ly: i32,
+    ) -> Result<(i32, i32, Vec<T>)> {
+        check_channel_type::<T>(
+            channel,
+            self.header().channel_pixel_type(channel),
+        )?;
+        let dw = self.data_window_for_level::<[i32; 4]>(lx, ly)?;
+        let w = dw[2] - dw[0] + 1;
+        let h = dw[3] - dw[1] + 1;
+        let mut pixels = vec![T::zero(); w as usize * h as usize];
+
+        let num_x_tiles = self.num_x_tiles(lx)?;
+        let num_y_tiles = self.num_y_tiles(ly)?;
+
+        let mut frame_buffer = FrameBuffer::new();
+        unsafe {
+            frame_buffer.insert_channel_with_fill(channel, &mut pixels, dw, 0.0)
+        }?;
+        self.set_frame_buffer(&frame_buffer)?;
+        let result =
+            self.read_tiles(0, num_x_tiles - 1, 0, num_y_tiles - 1, lx, ly);
+        // don't leave the file pointing at `pixels` once it is returned
+        self.set_frame_buffer(&FrameBuffer::new())?;
+        result?;
+
+        Ok((w, h, pixels))
+    }
 }
 
 #[cfg(test)]
@@ -608,6 +766,96 @@ mod tests {
         assert_eq!(is_complete, true);
     }
 
+    #[test]
+    fn test_tiledinputfile_tile_exists_success() {
+        let tiled_input_file = get_tiled_input_file();
+
+        assert_eq!(tiled_input_file.tile_exists(0, 0, 0, 0), true);
+        assert_eq!(tiled_input_file.tile_exists(18, 12, 0, 0), true);
+    }
+
+    #[test]
+    fn test_tiledinputfile_tile_exists_failure_invalid_tile() {
+        let tiled_input_file = get_tiled_input_file();
+
+        assert_eq!(tiled_input_file.tile_exists(19, 0, 0, 0), false);
+        assert_eq!(tiled_input_file.tile_exists(0, 13, 0, 0), false);
+        assert_eq!(tiled_input_file.tile_exists(-1, 0, 0, 0), false);
+        assert_eq!(tiled_input_file.tile_exists(0, 0, 100, 100), false);
+    }
+
+    #[test]
+    fn test_tiledinputfile_tile_exists_failure_missing_tile() {
+        use crate::core::{
+            channel_list::CHANNEL_HALF, header::Header,
+            tile_description::TileDescription, LineOrder,
+        };
+        use crate::tiled::tiled_output_file::TiledOutputFile;
+        use half::f16;
+
+        let path = "tiledinputfile_tile_exists_missing.exr";
+        let mut header = Header::from_dimensions(64, 32);
+        header.channels_mut().insert("Y", &CHANNEL_HALF);
+        header.set_line_order(LineOrder::RandomY);
+        header
+            .set_tile_description(&TileDescription::new(
+                16,
+                16,
+                LevelMode::OneLevel,
+                LevelRoundingMode::RoundDown,
+            ))
+            .unwrap();
+
+        let mut pixels = vec![f16::ONE; 64 * 32];
+        {
+            let mut file = TiledOutputFile::new(path, &header, 1).unwrap();
+            let mut frame_buffer = FrameBuffer::new();
+            unsafe {
+                frame_buffer.insert_channel_with_fill(
+                    "Y",
+                    &mut pixels,
+                    [0, 0, 63, 31],
+                    0.0,
+                )
+            }
+            .unwrap();
+            file.set_frame_buffer(&frame_buffer).unwrap();
+            // leave out every tile except (0, 0) and (2, 1)
+            file.write_tile(2, 1, 0, 0).unwrap();
+            file.write_tile(0, 0, 0, 0).unwrap();
+        }
+
+        let mut tiled_input_file = super::TiledInputFile::new(path, 0).unwrap();
+        let mut read = vec![f16::ZERO; 64 * 32];
+        let mut frame_buffer = FrameBuffer::new();
+        unsafe {
+            frame_buffer.insert_channel_with_fill(
+                "Y",
+                &mut read,
+                [0, 0, 63, 31],
+                0.0,
+            )
+        }
+        .unwrap();
+        tiled_input_file.set_frame_buffer(&frame_buffer).unwrap();
+
+        assert_eq!(tiled_input_file.is_complete(), false);
+        // ask for a missing tile first, so a check that reads whichever
+        // tile comes next in the file would get it wrong
+        assert_eq!(tiled_input_file.tile_exists(1, 0, 0, 0), false);
+        assert_eq!(tiled_input_file.tile_exists(2, 1, 0, 0), true);
+        assert_eq!(tiled_input_file.tile_exists(3, 1, 0, 0), false);
+        assert_eq!(tiled_input_file.tile_exists(0, 0, 0, 0), true);
+        assert_eq!(tiled_input_file.tile_exists(0, 1, 0, 0), false);
+
+        // the file's own frame buffer still reads the present tiles
+        tiled_input_file.read_tile(2, 1, 0, 0).unwrap();
+        assert_eq!(read[16 * 64 + 32], f16::ONE);
+        assert!(tiled_input_file.read_tile(1, 0, 0, 0).is_err());
+
+        let _ = std::fs::remove_file(path);
+    }
+
     #[test]
     fn test_tiledinputfile_tile_x_size_success() {
         let tiled_input_file = get_tiled_input_file();
@@ -821,4 +1069,157 @@ mod tests {
             )
             .unwrap();
     }
+
+    #[test]
+    fn test_tiledinputfile_read_all_levels_success() {
+        use half::f16;
+
+        let mut tiled_input_file = get_tiled_input_file();
+        let dw = *tiled_input_file.header().data_window::<[i32; 4]>();
+        let levels = tiled_input_file.read_all_levels::<f16>("R").unwrap();
+
+        assert_eq!(levels.len(), 1);
+        let (w, h, pixels) = &levels[0];
+        assert_eq!(*w, dw[2] - dw[0] + 1);
+        assert_eq!(*h, dw[3] - dw[1] + 1);
+        assert_eq!(pixels.len(), (*w * *h) as usize);
+        assert!(pixels.iter().any(|p| *p != f16::ZERO));
+
+        let ripmap_levels =
+            tiled_input_file.read_all_ripmap_levels::<f16>("R").unwrap();
+        assert_eq!(ripmap_levels.len(), 1);
+        assert_eq!(ripmap_levels[0].0, 0);
+        assert_eq!(ripmap_levels[0].1, 0);
+        assert_eq!(&ripmap_levels[0].4, pixels);
+    }
+
+    /// Write a 64x32 file with 16x16 tiles and level mode `mode` to `path`,
+    /// filling every level with a different pattern, and return the levels
+    /// as `(lx, ly, width, height, pixels)` in the order they were written
+    fn write_levels(
+        path: &str,
+        mode: LevelMode,
+    ) -> Vec<(i32, i32, i32, i32, Vec<half::f16>)> {
+        use crate::core::{
+            channel_list::CHANNEL_HALF, header::Header,
+            tile_description::TileDescription,
+        };
+        use crate::tiled::tiled_output_file::TiledOutputFile;
+        use half::f16;
+
+        let mut header = Header::from_dimensions(64, 32);
+        header.channels_mut().insert("Y", &CHANNEL_HALF);
+        header
+            .set_tile_description(&TileDescription::new(
+                16,
+                16,
+                mode,
+                LevelRoundingMode::RoundDown,
+            ))
+            .unwrap();
+
+        let mut file = TiledOutputFile::new(path, &header, 1).unwrap();
+        let mut levels = Vec::new();
+        for ly in 0..file.num_y_levels() {
+            for lx in 0..file.num_x_levels() {
+                if !file.is_valid_level(lx, ly) {
+                    continue;
+                }
+
+                let w = file.level_width(lx).unwrap();
+                let h = file.level_height(ly).unwrap();
+                let mut pixels = (0..w * h)
+                    .map(|i| {
+                        f16::from_f32(
+                            (lx * 8 + ly) as f32 + (i % 8) as f32 * 0.125,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut frame_buffer = FrameBuffer::new();
+                unsafe {
+                    frame_buffer.insert_channel_with_fill(
+                        "Y",
+                        &mut pixels,
+                        [0, 0, w - 1, h - 1],
+                        0.0,
+                    )
+                }
+                .unwrap();
+                file.set_frame_buffer(&frame_buffer).unwrap();
+                let num_x_tiles = file.num_x_tiles(lx).unwrap();
+                let num_y_tiles = file.num_y_tiles(ly).unwrap();
+                file.write_tiles(
+                    0,
+                    num_x_tiles - 1,
+                    0,
+                    num_y_tiles - 1,
+                    lx,
+                    ly,
+                )
+                .unwrap();
+
+                levels.push((lx, ly, w, h, pixels));
+            }
+        }
+
+        levels
+    }
+
+    #[test]
+    fn test_tiledinputfile_read_all_levels_mipmap_success() {
+        use half::f16;
+
+        let path = "tiledinputfile_read_all_levels_mipmap.exr";
+        let expected = write_levels(path, LevelMode::MipmapLevels);
+
+        let mut tiled_input_file = super::TiledInputFile::new(path, 1).unwrap();
+        assert_eq!(tiled_input_file.num_levels().unwrap(), 7);
+        let levels = tiled_input_file.read_all_levels::<f16>("Y").unwrap();
+        assert_eq!(levels.len(), 7);
+        assert_eq!(expected.len(), 7);
+        for (l, (w, h, pixels)) in levels.iter().enumerate() {
+            assert_eq!(*w, (64 >> l).max(1), "width of level {}", l);
+            assert_eq!(*h, (32 >> l).max(1), "height of level {}", l);
+
+            let (lx, ly, _, _, expected_pixels) = &expected[l];
+            assert_eq!((*lx, *ly), (l as i32, l as i32));
+            assert_eq!(pixels, expected_pixels, "pixels of level {}", l);
+        }
+
+        // only the levels with lx == ly exist in a mipmap
+        let ripmap_levels =
+            tiled_input_file.read_all_ripmap_levels::<f16>("Y").unwrap();
+        assert_eq!(ripmap_levels, expected);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_tiledinputfile_read_all_levels_ripmap_success() {
+        use half::f16;
+
+        let path = "tiledinputfile_read_all_levels_ripmap.exr";
+        let expected = write_levels(path, LevelMode::RipmapLevels);
+
+        let mut tiled_input_file = super::TiledInputFile::new(path, 1).unwrap();
+        assert_eq!(tiled_input_file.num_x_levels(), 7);
+        assert_eq!(tiled_input_file.num_y_levels(), 6);
+        assert!(tiled_input_file.read_all_levels::<f16>("Y").is_err());
+
+        let levels =
+            tiled_input_file.read_all_ripmap_levels::<f16>("Y").unwrap();
+        assert_eq!(levels.len(), 7 * 6);
+        for (lx, ly, w, h, pixels) in &levels {
+            assert_eq!(*w, (64 >> lx).max(1), "width of level {}, {}", lx, ly);
+            assert_eq!(*h, (32 >> ly).max(1), "height of level {}, {}", lx, ly);
+            assert_eq!(pixels.len(), (*w * *h) as usize);
+        }
+        // ordered by ly, then lx
+        assert_eq!((levels[1].0, levels[1].1), (1, 0));
+        assert_eq!((levels[7].0, levels[7].1), (0, 1));
+        assert_eq!(levels, expected);
+
+        let _ = std::fs::remove_file(path);
+    }
 }
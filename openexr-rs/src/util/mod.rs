@@ -1,2 +1,55 @@
 pub mod test_file;
 pub use test_file::is_openexr_file;
+
+use crate::core::error::Error;
+
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
+
+/// Convert `path` to the C string OpenEXR opens files with.
+///
+/// On Unix the bytes of the path are passed through unchanged, so paths that
+/// are not valid UTF-8 still work. Elsewhere OpenEXR expects UTF-8.
+///
+/// ## Errors
+/// * [`Error::InvalidArgument`] - if `path` contains a NUL byte, or is not
+/// valid UTF-8 on a platform other than Unix
+///
+pub(crate) fn path_to_cstring(path: &Path) -> Result<CString, Error> {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes()
+    };
+    #[cfg(not(unix))]
+    let bytes = path
+        .to_str()
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "path {:?} is not valid UTF-8",
+                path
+            ))
+        })?
+        .as_bytes();
+
+    Ok(CString::new(bytes)?)
+}
+
+/// Convert a file name returned by OpenEXR back to a path, the inverse of
+/// [`path_to_cstring()`].
+///
+/// On Unix the bytes are used unchanged. Elsewhere they are decoded as UTF-8,
+/// replacing invalid sequences.
+///
+pub(crate) fn cstr_to_path(name: &CStr) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(OsStr::from_bytes(name.to_bytes()))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(name.to_string_lossy().into_owned())
+    }
+}